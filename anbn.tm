@@ -0,0 +1,20 @@
+6 %states
+4 %accepting states
+5 %rejecting states
+a,b %input alphabet
+_,a,b,X,Y %tape alphabet, the first symbol is the blank
+0,a,1,X,R %state,read,target,write,direction
+0,Y,3,Y,R
+0,_,4,_,S
+0,b,5,b,S
+1,a,1,a,R
+1,Y,1,Y,R
+1,b,2,Y,L
+1,_,5,_,S
+2,a,2,a,L
+2,Y,2,Y,L
+2,X,0,X,R
+3,Y,3,Y,R
+3,_,4,_,S
+3,a,5,a,S
+3,b,5,b,S
@@ -0,0 +1,330 @@
+use std::{collections::HashMap, fmt::Display, fs::File, io::Read};
+
+use crate::dfa::{DFAEvaluationError, DFA};
+
+// a state on a depth-first search stack: its successors, the next one to try, and the
+// character on the edge used to reach it
+type SearchFrame = (i32, Vec<(char, i32)>, usize, Option<char>);
+
+#[derive(Debug, Clone)]
+pub struct Buchi {
+    pub alphabet: Vec<char>,
+    pub states: i32,
+    pub accepting: Vec<bool>,
+    pub transition: HashMap<(i32, char), Vec<i32>>,
+}
+
+impl Default for Buchi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Buchi {
+    pub fn new() -> Self {
+        Self {
+            alphabet: Vec::new(),
+            states: 0,
+            accepting: Vec::new(),
+            transition: HashMap::new(),
+        }
+    }
+
+    pub fn from_dfa(dfa: &DFA) -> Self {
+        let dfa = dfa.start_at_zero();
+        Buchi {
+            alphabet: dfa.alphabet.clone(),
+            states: dfa.states,
+            accepting: dfa.accepting.clone(),
+            transition: dfa.transition.iter().map(|(&key, &to)| (key, vec![to])).collect(),
+        }
+    }
+
+    // the deterministic automaton accepting exactly the word u·v^ω
+    pub fn lasso(u: &str, v: &str) -> Self {
+        let word: Vec<char> = u.chars().chain(v.chars()).collect();
+        let loop_start = u.chars().count() as i32;
+        let mut result = Buchi::new();
+        for &char in &word {
+            if !result.alphabet.contains(&char) {
+                result.alphabet.push(char);
+            }
+        }
+        result.states = word.len() as i32;
+        result.accepting = (0..result.states).map(|i| i >= loop_start).collect();
+        for (i, &char) in word.iter().enumerate() {
+            let next = if i + 1 == word.len() { loop_start } else { i as i32 + 1 };
+            result.transition.insert((i as i32, char), vec![next]);
+        }
+        result
+    }
+
+    pub fn open_buchi_file(path: &str) -> Result<Buchi, ()> {
+        let mut file1 = File::open(path);
+        if file1.is_err() {
+            file1 = File::open(format!("{path}.buchi"));
+        }
+        let mut contents1 = String::new();
+        match file1 {
+            Ok(mut file) => {
+                file.read_to_string(&mut contents1).map_err(|_| ())?;
+                Ok(Buchi::from_string(contents1))
+            }
+            Err(_) => Err(()),
+        }
+    }
+
+    // same layout as a .dfa file, except that each cell of the transition table holds
+    // any number of space separated target states
+    pub fn from_string(s: String) -> Self {
+        let mut result = Buchi::new();
+        for (i, line) in s.split('\n').enumerate() {
+            let line = line.split('%').next().unwrap();
+            match i {
+                0 => match line.trim().parse::<i32>() {
+                    Ok(a) => {
+                        result.states = a;
+                        result.accepting = vec![false; a as usize]
+                    }
+                    Err(_) => result.states = 0,
+                },
+                1 => {
+                    for s in line.split(',') {
+                        if let Ok(a) = s.trim().parse::<usize>() {
+                            if a < result.accepting.len() {
+                                result.accepting[a] = true;
+                            }
+                        }
+                    }
+                }
+                2 => {
+                    for s in line.split(',') {
+                        if let Some(a) = s.trim().chars().next() {
+                            result.alphabet.push(a);
+                        }
+                    }
+                }
+                _ => {
+                    for (j, s) in line.split(',').enumerate() {
+                        if j >= result.alphabet.len() {
+                            continue;
+                        }
+                        let targets: Vec<i32> = s.split_whitespace().filter_map(|t| t.parse().ok()).collect();
+                        if !targets.is_empty() {
+                            result.transition.insert((i as i32 - 3, result.alphabet[j]), targets);
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    pub fn to_string(&self) -> String {
+        let mut s = String::new();
+        s.push_str(&self.states.to_string());
+        s.push_str(" %states\n");
+        for i in 0..self.states {
+            if self.accepting[i as usize] {
+                s.push(',');
+                s.push_str(&i.to_string());
+            }
+        }
+        s.push_str(" %accepting states\n");
+        for char in &self.alphabet {
+            s.push(',');
+            s.push(*char);
+        }
+        s.push_str(" %alphabet\n");
+        for i in 0..self.states {
+            for char in &self.alphabet {
+                s.push(',');
+                if let Some(targets) = self.transition.get(&(i, *char)) {
+                    s.push_str(&targets.iter().map(|t| t.to_string()).collect::<Vec<String>>().join(" "));
+                }
+            }
+            s.push_str(" %");
+            s.push_str(&i.to_string());
+            s.push_str(":\n");
+        }
+        s.replace("\n,", "\n")
+    }
+
+    fn successors(&self, state: i32) -> Vec<(char, i32)> {
+        let mut result = vec![];
+        for &char in &self.alphabet {
+            if let Some(targets) = self.transition.get(&(state, char)) {
+                result.extend(targets.iter().map(|&t| (char, t)));
+            }
+        }
+        result
+    }
+
+    // nested depth-first search: whenever the outer search finishes an accepting state,
+    // an inner search looks for a cycle back to it. the outer stack is then the stem
+    // of the lasso and the inner stack its loop
+    pub fn accepting_lasso(&self) -> Option<(String, String)> {
+        if self.states == 0 {
+            return None;
+        }
+        let mut visited = vec![false; self.states as usize];
+        let mut flagged = vec![false; self.states as usize];
+        let mut outer: Vec<SearchFrame> = vec![(0, self.successors(0), 0, None)];
+        visited[0] = true;
+        while let Some((state, successors, index, _)) = outer.last_mut() {
+            if *index < successors.len() {
+                let (char, target) = successors[*index];
+                *index += 1;
+                if !visited[target as usize] {
+                    visited[target as usize] = true;
+                    outer.push((target, self.successors(target), 0, Some(char)));
+                }
+                continue;
+            }
+            let seed = *state;
+            if self.accepting[seed as usize] {
+                if let Some(cycle) = self.cycle_through(seed, &mut flagged) {
+                    let stem = outer.iter().filter_map(|(_, _, _, char)| *char).collect();
+                    return Some((stem, cycle));
+                }
+            }
+            outer.pop();
+        }
+        None
+    }
+
+    fn cycle_through(&self, seed: i32, flagged: &mut [bool]) -> Option<String> {
+        let mut inner: Vec<SearchFrame> = vec![(seed, self.successors(seed), 0, None)];
+        while let Some((_, successors, index, _)) = inner.last_mut() {
+            if *index >= successors.len() {
+                inner.pop();
+                continue;
+            }
+            let (char, target) = successors[*index];
+            *index += 1;
+            if target == seed {
+                let mut cycle: String = inner.iter().filter_map(|(_, _, _, char)| *char).collect();
+                cycle.push(char);
+                return Some(cycle);
+            }
+            if !flagged[target as usize] {
+                flagged[target as usize] = true;
+                inner.push((target, self.successors(target), 0, Some(char)));
+            }
+        }
+        None
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accepting_lasso().is_none()
+    }
+
+    pub fn accepts_lasso(&self, u: &str, v: &str) -> Result<bool, DFAEvaluationError> {
+        for char in u.chars().chain(v.chars()) {
+            if !self.alphabet.contains(&char) {
+                return Err(DFAEvaluationError::InvalidChar(char));
+            }
+        }
+        if v.is_empty() {
+            return Ok(false);
+        }
+        Ok(!self.intersect(&Buchi::lasso(u, v)).is_empty())
+    }
+
+    // two copies of the product: the run moves to the second copy after an accepting state
+    // of `self` and back after an accepting state of `rhs`, so visiting the switch infinitely
+    // often means both were visited infinitely often
+    pub fn intersect(&self, rhs: &Self) -> Self {
+        let mut result = Buchi::new();
+        result.alphabet = self
+            .alphabet
+            .iter()
+            .filter(|c| rhs.alphabet.contains(c))
+            .copied()
+            .collect();
+        if self.states == 0 || rhs.states == 0 {
+            return result;
+        }
+        let mut index: HashMap<(i32, i32, bool), i32> = HashMap::new();
+        let mut queue = vec![(0, 0, false)];
+        index.insert(queue[0], 0);
+        let mut next = 0;
+        while next < queue.len() {
+            let (p, q, second) = queue[next];
+            result.accepting.push(second && rhs.accepting[q as usize]);
+            let switch = if second {
+                !rhs.accepting[q as usize]
+            } else {
+                self.accepting[p as usize]
+            };
+            for &char in &result.alphabet {
+                let mut targets = vec![];
+                for &p2 in self.transition.get(&(p, char)).into_iter().flatten() {
+                    for &q2 in rhs.transition.get(&(q, char)).into_iter().flatten() {
+                        let target = (p2, q2, switch);
+                        let id = match index.get(&target) {
+                            Some(&id) => id,
+                            None => {
+                                let id = queue.len() as i32;
+                                index.insert(target, id);
+                                queue.push(target);
+                                id
+                            }
+                        };
+                        targets.push(id);
+                    }
+                }
+                if !targets.is_empty() {
+                    result.transition.insert((next as i32, char), targets);
+                }
+            }
+            next += 1;
+        }
+        result.states = queue.len() as i32;
+        result
+    }
+
+    pub fn union(&self, rhs: &Self) -> Self {
+        let mut result = Buchi::new();
+        result.alphabet = self.alphabet.clone();
+        for &char in &rhs.alphabet {
+            if !result.alphabet.contains(&char) {
+                result.alphabet.push(char);
+            }
+        }
+        result.states = 1 + self.states + rhs.states;
+        result.accepting = vec![false];
+        result.accepting.extend(&self.accepting);
+        result.accepting.extend(&rhs.accepting);
+        let offsets = [(self, 1), (rhs, 1 + self.states)];
+        for (automaton, offset) in offsets {
+            for (&(from, char), targets) in &automaton.transition {
+                let targets: Vec<i32> = targets.iter().map(|t| t + offset).collect();
+                if from == 0 {
+                    result.transition.entry((0, char)).or_default().extend(&targets);
+                }
+                result.transition.insert((from + offset, char), targets);
+            }
+        }
+        result
+    }
+
+    pub fn evaluate_to_string(&self, s: &str) -> String {
+        let (u, v) = match s.strip_suffix(')').and_then(|s| s.rsplit_once('(')) {
+            Some(a) => a,
+            None => return String::from("infinite words are written as u(v), meaning u followed by v forever"),
+        };
+        match self.accepts_lasso(u, v) {
+            Ok(true) => String::from("true"),
+            Ok(false) => String::from("false"),
+            Err(DFAEvaluationError::InvalidChar(c)) => format!("invalid character: {c}"),
+            Err(_) => String::from("false"),
+        }
+    }
+}
+
+impl Display for Buchi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
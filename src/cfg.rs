@@ -0,0 +1,675 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs::File,
+    io::Read,
+};
+
+use crate::{
+    dfa::DFA,
+    pda::{Acceptance, PDA},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Symbol {
+    Terminal(char),
+    Nonterminal(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Grammar {
+    pub start: String,
+    pub rules: Vec<(String, Vec<Symbol>)>,
+}
+
+#[derive(Debug)]
+pub enum GrammarError {
+    UnableToOpen(String),
+    InvalidLine(usize),
+    NoRules,
+}
+
+impl Grammar {
+    pub fn open_grammar_file(path: &str) -> Result<Grammar, GrammarError> {
+        let mut file1 = File::open(path);
+        if file1.is_err() {
+            file1 = File::open(format!("{path}.cfg"));
+        }
+        let mut contents = String::new();
+        match file1.and_then(|mut file| file.read_to_string(&mut contents)) {
+            Ok(_) => Grammar::from_string(&contents),
+            Err(_) => Err(GrammarError::UnableToOpen(path.to_string())),
+        }
+    }
+
+    // one rule per line, `A -> x B y | z` or `<A> ::= ...`; a line starting with `|`
+    // continues the previous rule. tokens that appear on a left-hand side are
+    // nonterminals, anything else is a run of terminals. quoting forces terminals,
+    // and an empty alternative or `ε` is the empty string
+    pub fn from_string(s: &str) -> Result<Grammar, GrammarError> {
+        let mut raw: Vec<(String, Vec<(String, bool)>)> = vec![];
+        let mut lhs = String::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = match find_unquoted(line, &["%"]) {
+                Some((j, _)) => line[..j].trim(),
+                None => line.trim(),
+            };
+            if line.is_empty() {
+                continue;
+            }
+            let rhs = if let Some(rest) = line.strip_prefix('|') {
+                if lhs.is_empty() {
+                    return Err(GrammarError::InvalidLine(i + 1));
+                }
+                rest
+            } else {
+                let (left, right) = match find_unquoted(line, &["::=", "->"]) {
+                    Some((j, arrow)) => (&line[..j], &line[j + arrow.len()..]),
+                    None => return Err(GrammarError::InvalidLine(i + 1)),
+                };
+                lhs = left.trim().to_string();
+                if lhs.is_empty() || lhs.contains(char::is_whitespace) {
+                    return Err(GrammarError::InvalidLine(i + 1));
+                }
+                right
+            };
+            for alternative in split_alternatives(rhs).ok_or(GrammarError::InvalidLine(i + 1))? {
+                raw.push((lhs.clone(), alternative));
+            }
+        }
+        if raw.is_empty() {
+            return Err(GrammarError::NoRules);
+        }
+        let nonterminals: HashSet<String> = raw.iter().map(|(lhs, _)| lhs.clone()).collect();
+        let rules = raw
+            .iter()
+            .map(|(lhs, tokens)| {
+                let mut rhs = vec![];
+                for (token, quoted) in tokens {
+                    if !quoted && nonterminals.contains(token) {
+                        rhs.push(Symbol::Nonterminal(token.clone()));
+                    } else if *quoted || !["ε", "eps", "epsilon"].contains(&token.as_str()) {
+                        rhs.extend(token.chars().map(Symbol::Terminal));
+                    }
+                }
+                (lhs.clone(), rhs)
+            })
+            .collect();
+        Ok(Grammar {
+            start: raw[0].0.clone(),
+            rules,
+        })
+    }
+
+    pub fn to_string(&self) -> String {
+        let mut s = String::new();
+        for nonterminal in self.nonterminals() {
+            let alternatives: Vec<String> = self
+                .rules
+                .iter()
+                .filter(|(lhs, _)| *lhs == nonterminal)
+                .map(|(_, rhs)| {
+                    if rhs.is_empty() {
+                        return String::from("ε");
+                    }
+                    rhs.iter()
+                        .map(|symbol| match symbol {
+                            Symbol::Terminal(c) if *c == '\'' || *c == '\\' => format!("'\\{c}'"),
+                            Symbol::Terminal(c) => format!("'{c}'"),
+                            Symbol::Nonterminal(n) => n.clone(),
+                        })
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .collect();
+            s.push_str(&format!("{nonterminal} -> {}\n", alternatives.join(" | ")));
+        }
+        s
+    }
+
+    pub fn nonterminals(&self) -> Vec<String> {
+        let mut result = vec![self.start.clone()];
+        for (lhs, rhs) in &self.rules {
+            for name in std::iter::once(lhs).chain(rhs.iter().filter_map(|symbol| match symbol {
+                Symbol::Nonterminal(n) => Some(n),
+                Symbol::Terminal(_) => None,
+            })) {
+                if !result.contains(name) {
+                    result.push(name.clone());
+                }
+            }
+        }
+        result
+    }
+
+    pub fn terminals(&self) -> Vec<char> {
+        let mut result = vec![];
+        for (_, rhs) in &self.rules {
+            for symbol in rhs {
+                if let Symbol::Terminal(c) = symbol {
+                    if !result.contains(c) {
+                        result.push(*c);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    fn fresh(&self, base: &str) -> String {
+        let used = self.nonterminals();
+        let mut name = base.to_string();
+        let mut i = 0;
+        while used.contains(&name) {
+            i += 1;
+            name = format!("{base}{i}");
+        }
+        name
+    }
+
+    fn nullable(&self) -> HashSet<String> {
+        let mut result = HashSet::new();
+        loop {
+            let before = result.len();
+            for (lhs, rhs) in &self.rules {
+                if rhs.iter().all(|symbol| match symbol {
+                    Symbol::Nonterminal(n) => result.contains(n),
+                    Symbol::Terminal(_) => false,
+                }) {
+                    result.insert(lhs.clone());
+                }
+            }
+            if result.len() == before {
+                return result;
+            }
+        }
+    }
+
+    // for every nonterminal that derives some terminal string, one of the shortest such strings
+    fn witnesses(&self) -> HashMap<String, String> {
+        let mut result: HashMap<String, String> = HashMap::new();
+        loop {
+            let mut changed = false;
+            for (lhs, rhs) in &self.rules {
+                let mut string = String::new();
+                let mut complete = true;
+                for symbol in rhs {
+                    match symbol {
+                        Symbol::Terminal(c) => string.push(*c),
+                        Symbol::Nonterminal(n) => match result.get(n) {
+                            Some(w) => string.push_str(w),
+                            None => {
+                                complete = false;
+                                break;
+                            }
+                        },
+                    }
+                }
+                if complete
+                    && result
+                        .get(lhs)
+                        .is_none_or(|w| w.chars().count() > string.chars().count())
+                {
+                    result.insert(lhs.clone(), string);
+                    changed = true;
+                }
+            }
+            if !changed {
+                return result;
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.witnesses().contains_key(&self.start)
+    }
+
+    pub fn witness(&self) -> Option<String> {
+        self.witnesses().remove(&self.start)
+    }
+
+    pub fn remove_useless(&self) -> Self {
+        let generating = self.witnesses();
+        let rules: Vec<(String, Vec<Symbol>)> = self
+            .rules
+            .iter()
+            .filter(|(lhs, rhs)| {
+                generating.contains_key(lhs)
+                    && rhs.iter().all(|symbol| match symbol {
+                        Symbol::Nonterminal(n) => generating.contains_key(n),
+                        Symbol::Terminal(_) => true,
+                    })
+            })
+            .cloned()
+            .collect();
+        let mut reachable = vec![self.start.clone()];
+        let mut next = 0;
+        while next < reachable.len() {
+            for (lhs, rhs) in &rules {
+                if *lhs != reachable[next] {
+                    continue;
+                }
+                for symbol in rhs {
+                    if let Symbol::Nonterminal(n) = symbol {
+                        if !reachable.contains(n) {
+                            reachable.push(n.clone());
+                        }
+                    }
+                }
+            }
+            next += 1;
+        }
+        Grammar {
+            start: self.start.clone(),
+            rules: rules.into_iter().filter(|(lhs, _)| reachable.contains(lhs)).collect(),
+        }
+    }
+
+    // the result only has rules of the form A -> B C and A -> a, plus S -> ε when the
+    // language contains the empty string, and the start symbol never appears on a right-hand side
+    pub fn to_cnf(&self) -> Self {
+        let mut result = self.clone();
+        let start = result.fresh(&format!("{}0", self.start));
+        result
+            .rules
+            .insert(0, (start.clone(), vec![Symbol::Nonterminal(self.start.clone())]));
+        result.start = start.clone();
+
+        let mut terminal_names: HashMap<char, String> = HashMap::new();
+        let mut rules = vec![];
+        for (lhs, rhs) in result.rules.clone() {
+            if rhs.len() < 2 {
+                rules.push((lhs, rhs));
+                continue;
+            }
+            let mut new_rhs = vec![];
+            for symbol in rhs {
+                match symbol {
+                    Symbol::Terminal(c) => {
+                        let name = match terminal_names.get(&c) {
+                            Some(name) => name.clone(),
+                            None => {
+                                let name = result.fresh(&format!("T_{}", name_part(c)));
+                                result.rules.push((name.clone(), vec![Symbol::Terminal(c)]));
+                                rules.push((name.clone(), vec![Symbol::Terminal(c)]));
+                                terminal_names.insert(c, name.clone());
+                                name
+                            }
+                        };
+                        new_rhs.push(Symbol::Nonterminal(name));
+                    }
+                    a => new_rhs.push(a),
+                }
+            }
+            rules.push((lhs, new_rhs));
+        }
+        result.rules = rules;
+
+        let mut rules = vec![];
+        for (lhs, rhs) in result.rules.clone() {
+            if rhs.len() <= 2 {
+                rules.push((lhs, rhs));
+                continue;
+            }
+            let mut current = lhs.clone();
+            for symbol in &rhs[..rhs.len() - 2] {
+                let name = result.fresh(&format!("{lhs}_"));
+                result.rules.push((name.clone(), vec![]));
+                rules.push((current, vec![symbol.clone(), Symbol::Nonterminal(name.clone())]));
+                current = name;
+            }
+            rules.push((current, rhs[rhs.len() - 2..].to_vec()));
+        }
+        result.rules = rules;
+
+        let nullable = result.nullable();
+        let mut rules = vec![];
+        for (lhs, rhs) in &result.rules {
+            let mut options: Vec<Vec<Symbol>> = vec![vec![]];
+            for symbol in rhs {
+                let mut next = vec![];
+                for option in &options {
+                    let mut with = option.clone();
+                    with.push(symbol.clone());
+                    next.push(with);
+                    if let Symbol::Nonterminal(n) = symbol {
+                        if nullable.contains(n) {
+                            next.push(option.clone());
+                        }
+                    }
+                }
+                options = next;
+            }
+            for option in options {
+                if option.is_empty() && *lhs != start {
+                    continue;
+                }
+                let rule = (lhs.clone(), option);
+                if !rules.contains(&rule) {
+                    rules.push(rule);
+                }
+            }
+        }
+        result.rules = rules;
+
+        let nonterminals = result.nonterminals();
+        let mut rules = vec![];
+        for a in &nonterminals {
+            let mut units = vec![a.clone()];
+            let mut next = 0;
+            while next < units.len() {
+                for (lhs, rhs) in &result.rules {
+                    if *lhs != units[next] {
+                        continue;
+                    }
+                    match rhs.as_slice() {
+                        [Symbol::Nonterminal(b)] => {
+                            if !units.contains(b) {
+                                units.push(b.clone());
+                            }
+                        }
+                        _ => {
+                            let rule = (a.clone(), rhs.clone());
+                            if !rules.contains(&rule) {
+                                rules.push(rule);
+                            }
+                        }
+                    }
+                }
+                next += 1;
+            }
+        }
+        result.rules = rules;
+        result.remove_useless()
+    }
+
+    pub fn cyk(&self, string: &str) -> bool {
+        let cnf = self.to_cnf();
+        let input: Vec<char> = string.chars().collect();
+        let n = input.len();
+        if n == 0 {
+            return cnf.rules.iter().any(|(lhs, rhs)| *lhs == cnf.start && rhs.is_empty());
+        }
+        let mut table: Vec<Vec<HashSet<&String>>> = vec![vec![HashSet::new(); n]; n];
+        for (i, c) in input.iter().enumerate() {
+            for (lhs, rhs) in &cnf.rules {
+                if rhs.as_slice() == [Symbol::Terminal(*c)] {
+                    table[0][i].insert(lhs);
+                }
+            }
+        }
+        for length in 2..=n {
+            for i in 0..=n - length {
+                for split in 1..length {
+                    for (lhs, rhs) in &cnf.rules {
+                        if let [Symbol::Nonterminal(b), Symbol::Nonterminal(c)] = rhs.as_slice() {
+                            if table[split - 1][i].contains(b) && table[length - split - 1][i + split].contains(c) {
+                                table[length - 1][i].insert(lhs);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        table[n - 1][0].contains(&cnf.start)
+    }
+
+    pub fn earley(&self, string: &str) -> bool {
+        let input: Vec<char> = string.chars().collect();
+        let nullable = self.nullable();
+        // items are (rule, dot, origin)
+        let mut sets: Vec<Vec<(usize, usize, usize)>> = vec![vec![]; input.len() + 1];
+        for (i, (lhs, _)) in self.rules.iter().enumerate() {
+            if *lhs == self.start {
+                sets[0].push((i, 0, 0));
+            }
+        }
+        for k in 0..=input.len() {
+            let mut next = 0;
+            while next < sets[k].len() {
+                let (rule, dot, origin) = sets[k][next];
+                let rhs = &self.rules[rule].1;
+                let mut new_items = vec![];
+                match rhs.get(dot) {
+                    Some(Symbol::Nonterminal(n)) => {
+                        for (i, (lhs, _)) in self.rules.iter().enumerate() {
+                            if lhs == n {
+                                new_items.push((i, 0, k));
+                            }
+                        }
+                        if nullable.contains(n) {
+                            new_items.push((rule, dot + 1, origin));
+                        }
+                    }
+                    Some(Symbol::Terminal(c)) => {
+                        if k < input.len() && input[k] == *c && !sets[k + 1].contains(&(rule, dot + 1, origin)) {
+                            sets[k + 1].push((rule, dot + 1, origin));
+                        }
+                    }
+                    None => {
+                        let lhs = &self.rules[rule].0;
+                        for &(r, d, o) in &sets[origin] {
+                            if self.rules[r].1.get(d) == Some(&Symbol::Nonterminal(lhs.clone())) {
+                                new_items.push((r, d + 1, o));
+                            }
+                        }
+                    }
+                }
+                for item in new_items {
+                    if !sets[k].contains(&item) {
+                        sets[k].push(item);
+                    }
+                }
+                next += 1;
+            }
+        }
+        sets[input.len()].iter().any(|&(rule, dot, origin)| {
+            origin == 0 && self.rules[rule].0 == self.start && dot == self.rules[rule].1.len()
+        })
+    }
+
+    // Bar-Hillel construction on the CNF grammar: nonterminal [p,A,q] derives exactly the
+    // strings that A derives and that also lead the automaton from state p to state q
+    pub fn intersect_dfa(&self, dfa: &DFA) -> Self {
+        let cnf = self.to_cnf();
+        let name = |p: i32, a: &str, q: i32| format!("[{p},{a},{q}]");
+        let start = String::from("S");
+        let mut rules = vec![];
+        for (lhs, rhs) in &cnf.rules {
+            match rhs.as_slice() {
+                [] => {
+                    for p in 0..dfa.states {
+                        rules.push((name(p, lhs, p), vec![]));
+                    }
+                }
+                [Symbol::Terminal(c)] => {
+                    for p in 0..dfa.states {
+                        if let Some(&q) = dfa.transition.get(&(p, *c)) {
+                            rules.push((name(p, lhs, q), vec![Symbol::Terminal(*c)]));
+                        }
+                    }
+                }
+                [Symbol::Nonterminal(b), Symbol::Nonterminal(c)] => {
+                    for p in 0..dfa.states {
+                        for r in 0..dfa.states {
+                            for q in 0..dfa.states {
+                                rules.push((
+                                    name(p, lhs, q),
+                                    vec![Symbol::Nonterminal(name(p, b, r)), Symbol::Nonterminal(name(r, c, q))],
+                                ));
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        for q in 0..dfa.states {
+            if dfa.accepting[q as usize] {
+                rules.push((start.clone(), vec![Symbol::Nonterminal(name(dfa.start, &cnf.start, q))]));
+            }
+        }
+        Grammar { start, rules }.remove_useless()
+    }
+
+    pub fn to_pda(&self) -> PDA {
+        let nonterminals = self.nonterminals();
+        let symbol =
+            |n: &String| char::from_u32(0xE000 + nonterminals.iter().position(|a| a == n).unwrap() as u32).unwrap();
+        let bottom = char::from_u32(0xE000 + nonterminals.len() as u32).unwrap();
+        let mut result = PDA::new();
+        result.states = 3;
+        result.accepting = vec![false, false, true];
+        result.alphabet = self.terminals();
+        result.stack_alphabet = vec![bottom];
+        result.stack_alphabet.extend(nonterminals.iter().map(symbol));
+        result.stack_alphabet.extend(result.alphabet.clone());
+        result.add_transition(0, None, bottom, 1, &format!("{}{bottom}", symbol(&self.start)));
+        for (lhs, rhs) in &self.rules {
+            let push: String = rhs
+                .iter()
+                .map(|s| match s {
+                    Symbol::Terminal(c) => *c,
+                    Symbol::Nonterminal(n) => symbol(n),
+                })
+                .collect();
+            result.add_transition(1, None, symbol(lhs), 1, &push);
+        }
+        for c in result.alphabet.clone() {
+            result.add_transition(1, Some(c), c, 1, "");
+        }
+        result.add_transition(1, None, bottom, 2, &bottom.to_string());
+        result
+    }
+
+    pub fn from_pda(pda: &PDA) -> Self {
+        let pda = match pda.acceptance {
+            Acceptance::EmptyStack => pda.clone(),
+            Acceptance::FinalState => pda.to_empty_stack_acceptance(),
+        };
+        let name = |p: i32, x: char, q: i32| format!("[{p},{},{q}]", name_part(x));
+        let start = String::from("S");
+        let mut rules = vec![];
+        if let Some(&bottom) = pda.stack_alphabet.first() {
+            for q in 0..pda.states {
+                rules.push((start.clone(), vec![Symbol::Nonterminal(name(0, bottom, q))]));
+            }
+        }
+        for (&(p, input, x), moves) in &pda.transition {
+            let prefix: Vec<Symbol> = input.map(Symbol::Terminal).into_iter().collect();
+            for (r, push) in moves {
+                let push: Vec<char> = push.chars().collect();
+                if push.is_empty() {
+                    rules.push((name(p, x, *r), prefix.clone()));
+                    continue;
+                }
+                // every choice of intermediate states s_1 .. s_k, where s_k is the final state q
+                let mut sequences: Vec<Vec<i32>> = vec![vec![]];
+                for _ in 0..push.len() {
+                    sequences = sequences
+                        .iter()
+                        .flat_map(|s| {
+                            (0..pda.states).map(move |q| {
+                                let mut s = s.clone();
+                                s.push(q);
+                                s
+                            })
+                        })
+                        .collect();
+                }
+                for sequence in sequences {
+                    let mut rhs = prefix.clone();
+                    let mut from = *r;
+                    for (y, &to) in push.iter().zip(&sequence) {
+                        rhs.push(Symbol::Nonterminal(name(from, *y, to)));
+                        from = to;
+                    }
+                    rules.push((name(p, x, *sequence.last().unwrap()), rhs));
+                }
+            }
+        }
+        Grammar { start, rules }.remove_useless()
+    }
+}
+
+impl Display for Grammar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+// a character as it appears in a generated nonterminal name; anything that could be read as
+// a quote, comment, separator or arrow is replaced by its code point
+fn name_part(c: char) -> String {
+    if c.is_alphanumeric() || c == '_' {
+        c.to_string()
+    } else {
+        format!("u{:x}", c as u32)
+    }
+}
+
+// the byte offset of the first of `patterns` that is not inside a quoted terminal
+fn find_unquoted<'a>(s: &str, patterns: &[&'a str]) -> Option<(usize, &'a str)> {
+    let mut quote = None;
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) if c == '\\' => {
+                chars.next();
+            }
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None => {
+                if let Some(pattern) = patterns.iter().find(|p| s[i..].starts_with(**p)) {
+                    return Some((i, pattern));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn split_alternatives(s: &str) -> Option<Vec<Vec<(String, bool)>>> {
+    let mut result = vec![vec![]];
+    let mut chars = s.chars().peekable();
+    let mut token = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                if !token.is_empty() {
+                    result.last_mut().unwrap().push((token.clone(), false));
+                    token.clear();
+                }
+                let mut quoted = String::new();
+                loop {
+                    match chars.next() {
+                        Some(a) if a == c => break,
+                        Some('\\') => quoted.push(chars.next()?),
+                        Some(a) => quoted.push(a),
+                        None => return None,
+                    }
+                }
+                result.last_mut().unwrap().push((quoted, true));
+            }
+            '|' => {
+                if !token.is_empty() {
+                    result.last_mut().unwrap().push((token.clone(), false));
+                    token.clear();
+                }
+                result.push(vec![]);
+            }
+            c if c.is_whitespace() => {
+                if !token.is_empty() {
+                    result.last_mut().unwrap().push((token.clone(), false));
+                    token.clear();
+                }
+            }
+            c => token.push(c),
+        }
+    }
+    if !token.is_empty() {
+        result.last_mut().unwrap().push((token, false));
+    }
+    Some(result)
+}
@@ -0,0 +1,370 @@
+use crate::dfa::DFA;
+
+// a code generator for one target language. `generate` returns the extension and contents
+// of every file it produces, main file first. each generated matcher maps characters to
+// table columns itself and reports characters outside the alphabet as invalid, like
+// `DFAEvaluationError::InvalidChar`
+pub trait Backend {
+    fn generate(&self, dfa: &DFA, name: &str) -> Vec<(&'static str, String)>;
+
+    // words the generated function can't be named, separated by spaces. these include the
+    // names of the helpers `generate` puts next to it
+    fn reserved_words(&self) -> &'static str;
+
+    // the function name for an output file: the file stem with anything but ascii letters and
+    // digits replaced by underscores, `matches` if that doesn't start with a letter, and an
+    // underscore appended to reserved words
+    fn function_name(&self, file_stem: &str) -> String {
+        let mut name = file_stem.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            name = String::from("matches");
+        }
+        if self.reserved_words().split_whitespace().any(|w| w == name) {
+            name.push('_');
+        }
+        name
+    }
+}
+
+pub struct Rust;
+pub struct C;
+pub struct Python;
+pub struct JavaScript;
+
+pub fn backend(language: &str) -> Option<Box<dyn Backend>> {
+    match language.to_lowercase().as_str() {
+        "rust" | "rs" => Some(Box::new(Rust)),
+        "c" => Some(Box::new(C)),
+        "python" | "py" => Some(Box::new(Python)),
+        "javascript" | "js" => Some(Box::new(JavaScript)),
+        _ => None,
+    }
+}
+
+// the accepting flags and transition table every backend embeds. a DFA without states
+// becomes a single rejecting state with no transitions
+struct Table {
+    accepting: Vec<bool>,
+    rows: Vec<Vec<Option<i32>>>,
+}
+
+impl Table {
+    // generated code always starts at state 0
+    fn new(dfa: &DFA) -> Self {
+        let dfa = &dfa.start_at_zero();
+        if dfa.states == 0 {
+            return Table {
+                accepting: vec![false],
+                rows: vec![vec![None; dfa.alphabet.len()]],
+            };
+        }
+        Table {
+            accepting: dfa.accepting.clone(),
+            rows: (0..dfa.states)
+                .map(|state| {
+                    dfa.alphabet
+                        .iter()
+                        .map(|char| dfa.transition.get(&(state, *char)).copied())
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    fn rows(&self, none: &str) -> Vec<String> {
+        self.rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|to| to.map_or(none.to_string(), |t| t.to_string()))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            })
+            .collect()
+    }
+
+    fn accepting(&self, t: &str, f: &str) -> String {
+        self.accepting
+            .iter()
+            .map(|&a| if a { t } else { f })
+            .collect::<Vec<&str>>()
+            .join(", ")
+    }
+}
+
+// an escape that python string literals and javascript string literals both understand
+// for printable ascii, with everything else written as a code point
+fn script_string(char: char, code_point: impl Fn(u32) -> String) -> String {
+    match char {
+        '\\' | '"' => format!("\"\\{char}\""),
+        ' '..='~' => format!("\"{char}\""),
+        _ => format!("\"{}\"", code_point(char as u32)),
+    }
+}
+
+impl Backend for Rust {
+    fn reserved_words(&self) -> &'static str {
+        "abstract as async await become box break const continue crate do dyn else enum extern false \
+         final fn for gen if impl in let loop macro match mod move mut override priv pub ref return self \
+         Self static struct super trait true try type typeof unsafe unsized use virtual where while yield"
+    }
+
+    fn generate(&self, dfa: &DFA, name: &str) -> Vec<(&'static str, String)> {
+        let table = Table::new(dfa);
+        let width = match table.rows.len() {
+            0..=0xFE => "u8",
+            0xFF..=0xFFFE => "u16",
+            _ => "u32",
+        };
+        let (states, columns) = (table.rows.len(), dfa.alphabet.len());
+        let mut s = String::new();
+        s.push_str(&format!("pub fn {name}(s: &str) -> bool {{\n"));
+        s.push_str(&format!("    {name}_evaluate(s).unwrap_or(false)\n}}\n\n"));
+        s.push_str("// Err(c) if c is not in the alphabet\n");
+        s.push_str(&format!("pub fn {name}_evaluate(s: &str) -> Result<bool, char> {{\n"));
+        if dfa.alphabet.is_empty() {
+            s.push_str("    match s.chars().next() {\n");
+            s.push_str("        Some(c) => Err(c),\n");
+            s.push_str(&format!("        None => Ok({}),\n", table.accepting[0]));
+            s.push_str("    }\n}\n");
+            return vec![("rs", s)];
+        }
+        s.push_str(&format!("    const NONE: {width} = {width}::MAX;\n"));
+        s.push_str(&format!(
+            "    const ACCEPTING: [bool; {states}] = [{}];\n",
+            table.accepting("true", "false")
+        ));
+        s.push_str(&format!("    const TABLE: [[{width}; {columns}]; {states}] = [\n"));
+        for row in table.rows("NONE") {
+            s.push_str(&format!("        [{row}],\n"));
+        }
+        s.push_str("    ];\n");
+        s.push_str("    let mut state = 0;\n");
+        s.push_str("    for c in s.chars() {\n");
+        s.push_str("        let column = match c {\n");
+        for (i, char) in dfa.alphabet.iter().enumerate() {
+            s.push_str(&format!("            {char:?} => {i},\n"));
+        }
+        s.push_str("            _ => return Err(c),\n");
+        s.push_str("        };\n");
+        s.push_str("        match TABLE[state][column] {\n");
+        s.push_str("            NONE => return Ok(false),\n");
+        s.push_str("            next => state = next as usize,\n");
+        s.push_str("        }\n");
+        s.push_str("    }\n");
+        s.push_str("    Ok(ACCEPTING[state])\n");
+        s.push_str("}\n");
+        vec![("rs", s)]
+    }
+}
+
+// a header and a source file. the input is utf-8, and malformed bytes are reported as the
+// invalid character U+FFFD
+impl Backend for C {
+    fn reserved_words(&self) -> &'static str {
+        "accepting alignas alignof auto bool break case char column const constexpr continue decode \
+         default do double else enum extern false float for goto if inline int long main nullptr register \
+         restrict return short signed size_t sizeof static static_assert struct switch table thread_local \
+         true typedef typeof typeof_unqual uint32_t union unsigned void volatile while"
+    }
+
+    fn generate(&self, dfa: &DFA, name: &str) -> Vec<(&'static str, String)> {
+        let table = Table::new(dfa);
+        let upper = name.to_uppercase();
+        let mut h = format!("#ifndef {upper}_H\n#define {upper}_H\n\n");
+        h.push_str("#include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n\n");
+        h.push_str(&format!(
+            "enum {name}_result {{ {upper}_REJECT = 0, {upper}_ACCEPT = 1, {upper}_INVALID_CHAR = -1 }};\n\n"
+        ));
+        h.push_str("/* runs the automaton on `length` bytes of utf-8. a character outside the alphabet gives\n");
+        h.push_str(&format!(
+            "   {upper}_INVALID_CHAR and, if `invalid_char` is not NULL, stores its code point there */\n"
+        ));
+        h.push_str(&format!(
+            "enum {name}_result {name}_evaluate(const char *s, size_t length, uint32_t *invalid_char);\n\n"
+        ));
+        h.push_str("/* true if the string is accepted, false if it is rejected or has invalid characters */\n");
+        h.push_str(&format!("bool {name}(const char *s, size_t length);\n\n#endif\n"));
+
+        // C has no zero length arrays, so an empty alphabet gets one unused column
+        let columns = dfa.alphabet.len().max(1);
+        let none = "UINT32_MAX";
+        let mut c = format!("#include \"{name}.h\"\n\n");
+        c.push_str(&format!(
+            "static const bool accepting[{}] = {{{}}};\n\n",
+            table.rows.len(),
+            table.accepting("true", "false")
+        ));
+        c.push_str(&format!(
+            "static const uint32_t table[{}][{columns}] = {{\n",
+            table.rows.len()
+        ));
+        for row in table.rows(none) {
+            let row = if row.is_empty() { none.to_string() } else { row };
+            c.push_str(&format!("    {{{row}}},\n"));
+        }
+        c.push_str("};\n\n");
+        c.push_str("static int column(uint32_t c) {\n    switch (c) {\n");
+        for (i, char) in dfa.alphabet.iter().enumerate() {
+            c.push_str(&format!("    case {}: return {i};\n", *char as u32));
+        }
+        c.push_str("    default: return -1;\n    }\n}\n\n");
+        c.push_str(
+            "static uint32_t decode(const unsigned char *s, size_t length, size_t *i) {
+    uint32_t c = s[*i];
+    size_t extra = c >= 0xF0 ? 3 : c >= 0xE0 ? 2 : c >= 0xC0 ? 1 : 0;
+    if (c >= 0x80 && (extra == 0 || c >= 0xF8 || *i + extra >= length)) {
+        *i += 1;
+        return 0xFFFD;
+    }
+    if (extra > 0) {
+        c &= 0x3F >> extra;
+    }
+    for (size_t k = 1; k <= extra; k++) {
+        if ((s[*i + k] & 0xC0) != 0x80) {
+            *i += 1;
+            return 0xFFFD;
+        }
+        c = (c << 6) | (s[*i + k] & 0x3F);
+    }
+    *i += extra + 1;
+    return c;
+}
+
+",
+        );
+        c.push_str(&format!(
+            "enum {name}_result {name}_evaluate(const char *s, size_t length, uint32_t *invalid_char) {{\n"
+        ));
+        c.push_str(&format!(
+            "    uint32_t state = 0;
+    size_t i = 0;
+    while (i < length) {{
+        uint32_t c = decode((const unsigned char *)s, length, &i);
+        int col = column(c);
+        if (col < 0) {{
+            if (invalid_char != NULL) {{
+                *invalid_char = c;
+            }}
+            return {upper}_INVALID_CHAR;
+        }}
+        if (table[state][col] == {none}) {{
+            return {upper}_REJECT;
+        }}
+        state = table[state][col];
+    }}
+    return accepting[state] ? {upper}_ACCEPT : {upper}_REJECT;
+}}
+
+bool {name}(const char *s, size_t length) {{
+    return {name}_evaluate(s, length, NULL) == {upper}_ACCEPT;
+}}
+"
+        ));
+        vec![("c", c), ("h", h)]
+    }
+}
+
+// `name(s)` raises InvalidChar, a ValueError, for characters outside the alphabet
+impl Backend for Python {
+    fn reserved_words(&self) -> &'static str {
+        "False InvalidChar None True and as assert async await break class continue def del elif else \
+         except finally for from global if import in is lambda nonlocal not or pass raise return try \
+         while with yield"
+    }
+
+    fn generate(&self, dfa: &DFA, name: &str) -> Vec<(&'static str, String)> {
+        let table = Table::new(dfa);
+        let upper = name.to_uppercase();
+        let mut s = String::from("class InvalidChar(ValueError):\n");
+        s.push_str("    def __init__(self, char):\n");
+        s.push_str("        super().__init__(f\"invalid character: {char}\")\n");
+        s.push_str("        self.char = char\n\n\n");
+        s.push_str(&format!(
+            "_{upper}_ACCEPTING = ({},)\n",
+            table.accepting("True", "False")
+        ));
+        s.push_str(&format!("_{upper}_TABLE = (\n"));
+        for row in table.rows("None") {
+            s.push_str(&format!("    ({row}{}),\n", if row.is_empty() { "" } else { "," }));
+        }
+        s.push_str(")\n");
+        let columns: Vec<String> = dfa
+            .alphabet
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| format!("{}: {i}", script_string(c, |u| format!("\\U{u:08x}"))))
+            .collect();
+        s.push_str(&format!("_{upper}_COLUMNS = {{{}}}\n\n\n", columns.join(", ")));
+        s.push_str(&format!("def {name}(s):\n"));
+        s.push_str("    state = 0\n");
+        s.push_str("    for c in s:\n");
+        s.push_str(&format!("        column = _{upper}_COLUMNS.get(c)\n"));
+        s.push_str("        if column is None:\n");
+        s.push_str("            raise InvalidChar(c)\n");
+        s.push_str(&format!("        state = _{upper}_TABLE[state][column]\n"));
+        s.push_str("        if state is None:\n");
+        s.push_str("            return False\n");
+        s.push_str(&format!("    return _{upper}_ACCEPTING[state]\n"));
+        vec![("py", s)]
+    }
+}
+
+// `name(s)` throws an Error with an `invalidChar` property for characters outside the alphabet
+impl Backend for JavaScript {
+    fn reserved_words(&self) -> &'static str {
+        "arguments await break case catch class const continue debugger default delete do else enum eval \
+         export exports extends false finally for function if implements import in instanceof interface \
+         let module new null package private protected public require return static super switch this \
+         throw true try typeof var void while with yield"
+    }
+
+    fn generate(&self, dfa: &DFA, name: &str) -> Vec<(&'static str, String)> {
+        let table = Table::new(dfa);
+        let upper = name.to_uppercase();
+        let mut s = format!("const {upper}_ACCEPTING = [{}];\n", table.accepting("true", "false"));
+        s.push_str(&format!("const {upper}_TABLE = [\n"));
+        for row in table.rows("-1") {
+            s.push_str(&format!("  [{row}],\n"));
+        }
+        s.push_str("];\n");
+        let columns: Vec<String> = dfa
+            .alphabet
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| format!("[{}, {i}]", script_string(c, |u| format!("\\u{{{u:x}}}"))))
+            .collect();
+        s.push_str(&format!(
+            "const {upper}_COLUMNS = new Map([{}]);\n\n",
+            columns.join(", ")
+        ));
+        s.push_str(&format!("function {name}(s) {{\n"));
+        s.push_str("  let state = 0;\n");
+        s.push_str("  for (const c of s) {\n");
+        s.push_str(&format!("    const column = {upper}_COLUMNS.get(c);\n"));
+        s.push_str("    if (column === undefined) {\n");
+        s.push_str("      const error = new Error(\"invalid character: \" + c);\n");
+        s.push_str("      error.invalidChar = c;\n");
+        s.push_str("      throw error;\n");
+        s.push_str("    }\n");
+        s.push_str(&format!("    state = {upper}_TABLE[state][column];\n"));
+        s.push_str("    if (state < 0) {\n");
+        s.push_str("      return false;\n");
+        s.push_str("    }\n");
+        s.push_str("  }\n");
+        s.push_str(&format!("  return {upper}_ACCEPTING[state];\n"));
+        s.push_str("}\n\n");
+        s.push_str("if (typeof module !== \"undefined\") {\n");
+        s.push_str(&format!("  module.exports = {{ {name} }};\n"));
+        s.push_str("}\n");
+        vec![("js", s)]
+    }
+}
+
+impl DFA {
+    // `fn_name(s: &str) -> bool` and `fn_name_evaluate(s: &str) -> Result<bool, char>`,
+    // running the automaton from a static transition table
+    pub fn to_rust(&self, fn_name: &str) -> String {
+        Rust.generate(self, fn_name).remove(0).1
+    }
+}
@@ -1,54 +1,302 @@
-use std::{cmp::Ordering, collections::HashMap, fmt::Display, fs::File, io::Read, vec};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{Debug, Display},
+    fs::File,
+    hash::Hash,
+    io::{Read, Write},
+    ops::Range,
+    vec,
+};
+
+use crate::{
+    dfa_binary::{BinaryError, DfaView, MAGIC},
+    dfa_jff::JffError,
+    dfa_json::DFAJsonError,
+    dfa_v2::{self, DFAParseError},
+};
+
+// anything that can label a transition. `parse` reads one entry of the alphabet line of a
+// .dfa file and `to_text` writes it back
+pub trait Symbol: Clone + Eq + Hash + Debug {
+    fn parse(s: &str) -> Option<Self>;
+    fn to_text(&self) -> String;
+}
+
+impl Symbol for char {
+    fn parse(s: &str) -> Option<Self> {
+        s.chars().next()
+    }
+
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+// bytes are written as decimal numbers
+impl Symbol for u8 {
+    fn parse(s: &str) -> Option<Self> {
+        s.parse().ok()
+    }
+
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+// tokens are written with the escapes \\ \n \r \t and \u{hex}, the last one for the
+// separators , and % and for whitespace at either end, which the reader would trim
+impl Symbol for String {
+    fn parse(s: &str) -> Option<Self> {
+        if s.is_empty() {
+            return None;
+        }
+        let mut result = String::new();
+        let mut chars = s.chars();
+        while let Some(char) = chars.next() {
+            if char != '\\' {
+                result.push(char);
+                continue;
+            }
+            result.push(match chars.next()? {
+                '\\' => '\\',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'u' => {
+                    if chars.next()? != '{' {
+                        return None;
+                    }
+                    let hex: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                    char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                }
+                _ => return None,
+            });
+        }
+        Some(result)
+    }
+
+    fn to_text(&self) -> String {
+        let last = self.chars().count().saturating_sub(1);
+        self.chars()
+            .enumerate()
+            .map(|(i, char)| match char {
+                '\\' => String::from("\\\\"),
+                '\n' => String::from("\\n"),
+                '\r' => String::from("\\r"),
+                '\t' => String::from("\\t"),
+                ',' | '%' => format!("\\u{{{:x}}}", char as u32),
+                _ if char.is_whitespace() && (i == 0 || i == last) => format!("\\u{{{:x}}}", char as u32),
+                _ => char.to_string(),
+            })
+            .collect()
+    }
+}
 
-#[derive(Debug, Clone)]
-pub struct DFA {
-    pub alphabet: Vec<char>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenericDFA<S: Symbol> {
+    pub alphabet: Vec<S>,
     pub states: i32,
+    pub start: i32,
     pub accepting: Vec<bool>,
-    pub transition: HashMap<(i32, char), i32>,
+    pub transition: HashMap<(i32, S), i32>,
+    // optional state names, kept by the formats that can store them
+    pub names: HashMap<i32, String>,
+    // missing transitions lead to an implicit dead state instead of being an error
+    pub partial: bool,
 }
 
+pub type DFA = GenericDFA<char>;
+
 #[derive(Debug)]
-pub enum DFAEvaluationError {
-    InvalidChar(char),
+pub enum DFAEvaluationError<S = char> {
+    InvalidChar(S),
     InvalidState(i32),
-    NoTransition((i32, char)),
+    NoTransition((i32, S)),
 }
 
-impl Default for DFA {
+#[derive(Debug)]
+pub enum DFAFileError {
+    Io(String),
+    NotUtf8,
+    Binary(BinaryError),
+    Json(DFAJsonError),
+    Jff(JffError),
+    Parse(DFAParseError),
+}
+
+impl<S: Symbol> Default for GenericDFA<S> {
     fn default() -> Self {
         Self::new()
     }
 }
 
 impl DFA {
+    pub fn open_dfa_file(path: &str) -> Result<DFA, DFAFileError> {
+        let mut file1 = File::open(path);
+        if file1.is_err() {
+            file1 = File::open(format!("{path}.dfa")).map_err(|_| file1.unwrap_err());
+        }
+        let mut bytes = vec![];
+        file1
+            .and_then(|mut file| file.read_to_end(&mut bytes))
+            .map_err(|e| DFAFileError::Io(e.to_string()))?;
+        if bytes.starts_with(MAGIC) {
+            return Ok(DfaView::new(&bytes).map_err(DFAFileError::Binary)?.to_dfa());
+        }
+        let contents1 = String::from_utf8(bytes).map_err(|_| DFAFileError::NotUtf8)?;
+        if path.ends_with(".json") {
+            return DFA::from_json(&contents1).map_err(DFAFileError::Json);
+        }
+        if path.ends_with(".jff") {
+            return DFA::from_jff(&contents1).map_err(DFAFileError::Jff);
+        }
+        DFA::from_string(contents1).map_err(DFAFileError::Parse)
+    }
+
+    // writes JSON for .json paths, JFLAP XML for .jff paths, the binary format for .bin paths
+    // and the .dfa format otherwise. partial automata are written in version 2, since version 1
+    // has no partial flag
+    pub fn save_dfa_file(&self, path: &str) -> Result<(), ()> {
+        let contents = if path.ends_with(".json") {
+            self.to_json().into_bytes()
+        } else if path.ends_with(".jff") {
+            self.to_jff().into_bytes()
+        } else if path.ends_with(".bin") {
+            self.to_binary()
+        } else if self.partial {
+            self.to_v2_string().into_bytes()
+        } else {
+            self.to_string().into_bytes()
+        };
+        File::create(path)
+            .and_then(|mut file| file.write_all(&contents))
+            .map_err(|_| ())
+    }
+
+    // v2 files are recognised by their header line, anything else is read as v1
+    pub fn from_string(s: String) -> Result<Self, DFAParseError> {
+        if dfa_v2::is_v2(&s) {
+            return DFA::from_v2_string(&s);
+        }
+        DFA::from_v1_string(&s)
+    }
+
+    pub fn evaluate(&self, string: &str) -> Result<bool, DFAEvaluationError> {
+        self.evaluate_symbols(string.chars())
+    }
+
+    // a partial automaton stops at a missing transition, like evaluate
+    pub fn trace(&self, string: &str) -> Result<Vec<(i32, char, i32)>, DFAEvaluationError> {
+        use DFAEvaluationError::*;
+        let mut result = vec![];
+        let mut state = self.start;
+        for char in string.chars() {
+            match self.step(state, char) {
+                Ok(next) => {
+                    result.push((state, char, next));
+                    state = next;
+                }
+                Err(NoTransition(_)) if self.partial => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(result)
+    }
+
+    // the steps taken before any error are shown, followed by the result or the error
+    pub fn trace_to_string(&self, s: &str) -> String {
+        let dead = self.dead_states();
+        let is_dead = |state: i32| dead.get(state as usize).copied().unwrap_or(false);
+        let mut result = String::from("step\tfrom\tchar\tto\n");
+        let mut rejecting = is_dead(self.start);
+        if rejecting {
+            result.push_str(&format!("start state {} is permanently rejecting\n", self.start));
+        }
+        let mut state = self.start;
+        for (i, char) in s.chars().enumerate() {
+            let Ok(to) = self.step(state, char) else {
+                break;
+            };
+            result.push_str(&format!("{}\t{state}\t{char}\t{to}", i + 1));
+            if !rejecting && is_dead(to) {
+                rejecting = true;
+                result.push_str("\t<- entered permanently rejecting state");
+            }
+            result.push('\n');
+            state = to;
+        }
+        result.push_str(&self.evaluate_to_string(s));
+        result
+    }
+
+    pub fn evaluate_to_string(&self, s: &str) -> String {
+        use DFAEvaluationError::*;
+        match self.evaluate(s) {
+            Ok(true) => String::from("true"),
+            Ok(false) => String::from("false"),
+            Err(InvalidChar(c)) => format!("invalid character: {c}"),
+            Err(InvalidState(i)) => format!("invalid state: {i}"),
+            Err(NoTransition((i, c))) => {
+                format!("no transition found for character {c} and state {i}")
+            }
+        }
+    }
+
+    fn longest_match_at(&self, string: &str, start: usize, dead: &[bool]) -> Option<usize> {
+        let mut state = self.start;
+        let mut result = None;
+        if self.accepting[state as usize] {
+            result = Some(start);
+        }
+        for (i, char) in string[start..].char_indices() {
+            match self.transition.get(&(state, char)) {
+                Some(&a) if (a as usize) < dead.len() && !dead[a as usize] => state = a,
+                _ => break,
+            }
+            if self.accepting[state as usize] {
+                result = Some(start + i + char.len_utf8());
+            }
+        }
+        result
+    }
+
+    pub fn find(&self, string: &str) -> Option<Range<usize>> {
+        self.find_iter(string).next()
+    }
+
+    pub fn find_iter<'a>(&'a self, string: &'a str) -> Matches<'a> {
+        Matches {
+            dfa: self,
+            string,
+            position: 0,
+            dead: self.dead_states(),
+        }
+    }
+
+    pub fn is_match_anywhere(&self, string: &str) -> bool {
+        self.find(string).is_some()
+    }
+}
+
+impl<S: Symbol> GenericDFA<S> {
     pub fn new() -> Self {
         Self {
             alphabet: Vec::new(),
             states: 0,
+            start: 0,
             accepting: Vec::new(),
             transition: HashMap::new(),
+            names: HashMap::new(),
+            partial: false,
         }
     }
 
-    pub fn open_dfa_file(path: &str) -> Result<DFA, ()> {
-        let mut file1 = File::open(path);
-        if file1.is_err() {
-            file1 = File::open(format!("{path}.dfa"));
-        }
-        let mut contents1 = String::new();
-        match file1 {
-            Ok(_) => {
-                file1.unwrap().read_to_string(&mut contents1).unwrap();
-                Ok(DFA::from_string(contents1))
-            }
-            Err(_) => Err(()),
-        }
-    }
-
-    pub fn from_string(s: String) -> Self {
+    // the positional .dfa format, with each alphabet entry read by `Symbol::parse`. an entry it
+    // rejects is an error, since skipping it would shift every later column of the table
+    pub fn from_v1_string(s: &str) -> Result<Self, DFAParseError> {
         let lines: Vec<&str> = s.split('\n').collect();
-        let mut result = DFA::new();
+        let mut result = Self::new();
         for (i, line) in lines.iter().enumerate() {
             match i {
                 0 => match line.split('%').next().unwrap().trim().parse::<i32>() {
@@ -67,18 +315,19 @@ impl DFA {
                     }
                 }
                 2 => {
-                    for s in line.split('%').next().unwrap().split(',') {
-                        result.alphabet.push(match s.trim().chars().next() {
-                            Some(a) => a,
-                            None => continue,
-                        });
+                    for s in line.split('%').next().unwrap().split(',').map(str::trim) {
+                        match S::parse(s) {
+                            Some(a) => result.alphabet.push(a),
+                            None if s.is_empty() => continue,
+                            None => return Err(DFAParseError::InvalidAlphabetEntry((i + 1, s.to_string()))),
+                        }
                     }
                 }
                 _ => {
                     for (j, s) in line.split('%').next().unwrap().split(',').enumerate() {
                         if j < result.alphabet.len() {
                             result.transition.insert(
-                                (i as i32 - 3, result.alphabet[j]),
+                                (i as i32 - 3, result.alphabet[j].clone()),
                                 match s.trim().parse::<i32>() {
                                     Ok(a) => a,
                                     Err(_) => continue,
@@ -89,10 +338,16 @@ impl DFA {
                 }
             }
         }
-        result
+        Ok(result)
     }
 
+    // the .dfa format always starts at state 0, so the start state is swapped with it first.
+    // missing transitions are written as -, but the partial flag is lost, so `save_dfa_file`
+    // uses version 2 for partial automata
     pub fn to_string(&self) -> String {
+        if self.start != 0 {
+            return self.start_at_zero().to_string();
+        }
         let mut s = String::new();
         s.push_str(&self.states.to_string());
         s.push_str(" %states\n");
@@ -103,15 +358,18 @@ impl DFA {
             }
         }
         s.push_str(" %accepting states\n");
-        for char in &self.alphabet {
+        for symbol in &self.alphabet {
             s.push(',');
-            s.push(*char);
+            s.push_str(&symbol.to_text());
         }
         s.push_str(" %alphabet\n");
         for i in 0..self.states {
-            for char in &self.alphabet {
+            for symbol in &self.alphabet {
                 s.push(',');
-                s.push_str(&self.transition.get(&(i, *char)).unwrap().to_string());
+                match self.transition.get(&(i, symbol.clone())) {
+                    Some(to) => s.push_str(&to.to_string()),
+                    None => s.push('-'),
+                }
             }
             s.push_str(" %");
             s.push_str(&i.to_string());
@@ -120,50 +378,113 @@ impl DFA {
         s.replace("\n,", "\n")
     }
 
-    pub fn evaluate(&self, string: &str) -> Result<bool, DFAEvaluationError> {
+    // evaluates any sequence of symbols, such as the bytes of a message or a list of words
+    pub fn evaluate_symbols<I: IntoIterator<Item = S>>(&self, symbols: I) -> Result<bool, DFAEvaluationError<S>> {
         use DFAEvaluationError::*;
-        let mut state = 0;
-        for char in string.chars() {
-            if !self.alphabet.contains(&char) {
-                return Err(InvalidChar(char));
-            }
-            if state >= self.states {
-                return Err(InvalidState(state));
-            }
-            match self.transition.get(&(state, char)) {
-                Some(&a) => state = a,
-                None => return Err(NoTransition((state, char))),
+        let mut state = self.start;
+        for symbol in symbols {
+            match self.step(state, symbol) {
+                Ok(a) => state = a,
+                Err(NoTransition(_)) if self.partial => return Ok(false),
+                Err(e) => return Err(e),
             }
         }
         Ok(self.accepting[state as usize])
     }
 
-    pub fn evaluate_to_string(&self, s: &str) -> String {
+    // the state reached from `state` on `symbol`
+    fn step(&self, state: i32, symbol: S) -> Result<i32, DFAEvaluationError<S>> {
         use DFAEvaluationError::*;
-        match self.evaluate(s) {
-            Ok(true) => String::from("true"),
-            Ok(false) => String::from("false"),
-            Err(InvalidChar(c)) => format!("invalid character: {c}"),
-            Err(InvalidState(i)) => format!("invalid state: {i}"),
-            Err(NoTransition((i, c))) => {
-                format!("no transition found for character {c} and state {i}")
+        if !self.alphabet.contains(&symbol) {
+            return Err(InvalidChar(symbol));
+        }
+        if state >= self.states {
+            return Err(InvalidState(state));
+        }
+        match self.transition.get(&(state, symbol.clone())) {
+            Some(&a) => Ok(a),
+            None => Err(NoTransition((state, symbol))),
+        }
+    }
+
+    fn dead_states(&self) -> Vec<bool> {
+        (0..self.states).map(|i| self.is_permanently_rejecting(i)).collect()
+    }
+
+    // parallel transitions merged into one edge per pair of states, in alphabet order,
+    // leaving out the edges of hidden states
+    pub(crate) fn merged_edges(&self, hide_dead: bool) -> Vec<(i32, i32, Vec<S>)> {
+        let hidden = self.hidden_states(hide_dead);
+        let mut result: Vec<(i32, i32, Vec<S>)> = vec![];
+        for from in (0..self.states).filter(|&s| !hidden[s as usize]) {
+            for symbol in &self.alphabet {
+                let to = match self.transition.get(&(from, symbol.clone())) {
+                    Some(&to) if !hidden[to as usize] => to,
+                    _ => continue,
+                };
+                match result.iter_mut().find(|(f, t, _)| *f == from && *t == to) {
+                    Some((_, _, symbols)) => symbols.push(symbol.clone()),
+                    None => result.push((from, to, vec![symbol.clone()])),
+                }
+            }
+        }
+        result
+    }
+
+    // the dead states other than the start state if `hide_dead` is set
+    pub(crate) fn hidden_states(&self, hide_dead: bool) -> Vec<bool> {
+        let dead = self.dead_states();
+        (0..self.states)
+            .map(|s| hide_dead && s != self.start && dead[s as usize])
+            .collect()
+    }
+
+    // states are placed in columns by their breadth-first distance from the start state, with
+    // unreachable states in a column of their own at the end
+    pub(crate) fn bfs_layers(&self) -> Vec<Vec<i32>> {
+        let mut layer = vec![None; self.states as usize];
+        let mut layers: Vec<Vec<i32>> = vec![];
+        if self.states > 0 {
+            layer[self.start as usize] = Some(0);
+            layers.push(vec![self.start]);
+        }
+        while let Some(last) = layers.last().cloned() {
+            let mut next = vec![];
+            for from in last {
+                for symbol in &self.alphabet {
+                    if let Some(&to) = self.transition.get(&(from, symbol.clone())) {
+                        if layer[to as usize].is_none() {
+                            layer[to as usize] = Some(layers.len());
+                            next.push(to);
+                        }
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
             }
+            layers.push(next);
+        }
+        let unreachable: Vec<i32> = (0..self.states).filter(|&i| layer[i as usize].is_none()).collect();
+        if !unreachable.is_empty() {
+            layers.push(unreachable);
         }
+        layers
     }
 
-    pub fn add_char_ignore(&self, char: char) -> Self {
+    pub fn add_char_ignore(&self, char: S) -> Self {
         let mut result = self.clone();
         if self.alphabet.contains(&char) {
             return result;
         }
-        result.alphabet.push(char);
+        result.alphabet.push(char.clone());
         result
             .transition
-            .extend((0..self.states).map(|state| ((state, char), state)));
+            .extend((0..self.states).map(|state| ((state, char.clone()), state)));
         result
     }
 
-    pub fn add_char_imitate(&self, char: char, other: char) -> Self {
+    pub fn add_char_imitate(&self, char: S, other: S) -> Self {
         let mut result = self.clone();
         if self.alphabet.contains(&char) {
             return result;
@@ -171,27 +492,28 @@ impl DFA {
         if !self.alphabet.contains(&other) {
             return result;
         }
-        result.alphabet.push(char);
-        result
-            .transition
-            .extend((0..self.states).map(|state| ((state, char), *self.transition.get(&(state, other)).unwrap())));
+        result.alphabet.push(char.clone());
+        result.transition.extend(
+            (0..self.states)
+                .filter_map(|state| Some(((state, char.clone()), *self.transition.get(&(state, other.clone()))?))),
+        );
         result
     }
 
-    pub fn add_char_accept(&self, char: char, accept: bool) -> Self {
+    pub fn add_char_accept(&self, char: S, accept: bool) -> Self {
         let mut result = self.clone();
         if self.alphabet.contains(&char) {
             return result;
         }
         result.states += 1;
         result.accepting.push(accept);
-        result.alphabet.push(char);
+        result.alphabet.push(char.clone());
         result
             .transition
-            .extend((0..self.states).map(|state| ((state, char), self.states)));
+            .extend((0..self.states).map(|state| ((state, char.clone()), self.states)));
         result
             .transition
-            .extend(result.alphabet.iter().map(|c: &char| ((self.states, *c), self.states)));
+            .extend(result.alphabet.iter().map(|c| ((self.states, c.clone()), self.states)));
         result
     }
 
@@ -199,17 +521,18 @@ impl DFA {
         if self.states == 0 {
             return rhs.clone();
         }
-        let mut lhs_clone = self.clone();
-        let mut rhs_clone = rhs.clone();
-        for &char in &self.alphabet {
-            rhs_clone = rhs_clone.add_char_accept(char, false)
+        let mut lhs_clone = self.complete();
+        let mut rhs_clone = rhs.complete();
+        for char in &self.alphabet {
+            rhs_clone = rhs_clone.add_char_accept(char.clone(), false)
         }
-        for &char in &rhs.alphabet {
-            lhs_clone = lhs_clone.add_char_accept(char, false)
+        for char in &rhs.alphabet {
+            lhs_clone = lhs_clone.add_char_accept(char.clone(), false)
         }
-        let mut result = DFA::new();
+        let mut result = Self::new();
         result.alphabet = lhs_clone.alphabet.clone();
         result.states = lhs_clone.states * rhs_clone.states;
+        result.start = lhs_clone.start * rhs_clone.states + rhs_clone.start;
         result.accepting = vec![false; result.states as usize];
         for i in 0..lhs_clone.states {
             for j in 0..rhs_clone.states {
@@ -217,9 +540,9 @@ impl DFA {
                     lhs_clone.accepting[i as usize] && rhs_clone.accepting[j as usize];
                 for char in &result.alphabet {
                     result.transition.insert(
-                        (i * rhs_clone.states + j, *char),
-                        *lhs_clone.transition.get(&(i, *char)).unwrap() * rhs_clone.states
-                            + *rhs_clone.transition.get(&(j, *char)).unwrap(),
+                        (i * rhs_clone.states + j, char.clone()),
+                        *lhs_clone.transition.get(&(i, char.clone())).unwrap() * rhs_clone.states
+                            + *rhs_clone.transition.get(&(j, char.clone())).unwrap(),
                     );
                 }
             }
@@ -228,7 +551,7 @@ impl DFA {
     }
 
     pub fn negation(&self) -> Self {
-        let mut result = self.clone();
+        let mut result = self.complete();
         result.accepting = result.accepting.iter().map(|a| !a).collect();
         result.optimize()
     }
@@ -236,11 +559,11 @@ impl DFA {
     pub fn union(&self, rhs: &Self) -> Self {
         let mut lhs_clone = self.clone();
         let mut rhs_clone = rhs.clone();
-        for &char in &self.alphabet {
-            rhs_clone = rhs_clone.add_char_accept(char, false)
+        for char in &self.alphabet {
+            rhs_clone = rhs_clone.add_char_accept(char.clone(), false)
         }
-        for &char in &rhs.alphabet {
-            lhs_clone = lhs_clone.add_char_accept(char, false)
+        for char in &rhs.alphabet {
+            lhs_clone = lhs_clone.add_char_accept(char.clone(), false)
         }
         lhs_clone.negation().intersect(&rhs_clone.negation()).negation()
     }
@@ -250,60 +573,48 @@ impl DFA {
     }
 
     pub fn xor(&self, rhs: &Self) -> Self {
-        self.union(&rhs).difference(&self.intersect(&rhs))
+        self.union(rhs).difference(&self.intersect(rhs))
     }
 
-    pub fn big_intersect(dfas: &[DFA]) -> Self {
-        dfas.iter().fold(DFA::new(), |old, new| old.intersect(&new))
+    pub fn big_intersect(dfas: &[Self]) -> Self {
+        dfas.iter().fold(Self::new(), |old, new| old.intersect(new))
     }
 
-    pub fn big_union(dfas: &[DFA]) -> Self {
-        dfas.iter().fold(DFA::new(), |old, new| old.union(&new))
+    pub fn big_union(dfas: &[Self]) -> Self {
+        dfas.iter().fold(Self::new(), |old, new| old.union(new))
     }
 
+    // transitions into `state` are redirected to `replacement`, which also becomes the start
+    // state if `state` was the start
     pub fn remove_state(&self, state: i32, mut replacement: i32) -> Result<Self, ()> {
         if state >= self.states || replacement >= self.states || replacement == self.states {
             return Err(());
         }
-        if state == 0 && replacement > 1 {
-            return Err(());
-        }
         if replacement > state {
             replacement -= 1;
         }
-        let mut result = DFA {
+        let renumber = |i: i32| match i.cmp(&state) {
+            Ordering::Less => i,
+            Ordering::Equal => replacement,
+            Ordering::Greater => i - 1,
+        };
+        let mut result = Self {
             alphabet: self.alphabet.clone(),
             states: self.states - 1,
+            start: renumber(self.start),
             accepting: vec![false; self.states as usize - 1],
             transition: HashMap::new(),
+            names: HashMap::new(),
+            partial: self.partial,
         };
-        for i in 0..self.states {
-            match i.cmp(&state) {
-                Ordering::Less => result.accepting[i as usize] = self.accepting[i as usize],
-                Ordering::Equal => continue,
-                Ordering::Greater => result.accepting[i as usize - 1] = self.accepting[i as usize],
-            }
-            for &char in &self.alphabet {
-                if i < state {
-                    match self.transition.get(&(i, char)).unwrap().cmp(&state) {
-                        Ordering::Less => result
-                            .transition
-                            .insert((i, char), *self.transition.get(&(i, char)).unwrap()),
-                        Ordering::Equal => result.transition.insert((i, char), replacement),
-                        Ordering::Greater => result
-                            .transition
-                            .insert((i, char), *self.transition.get(&(i, char)).unwrap() - 1),
-                    };
-                } else {
-                    match self.transition.get(&(i, char)).unwrap().cmp(&state) {
-                        Ordering::Less => result
-                            .transition
-                            .insert((i - 1, char), *self.transition.get(&(i, char)).unwrap()),
-                        Ordering::Equal => result.transition.insert((i - 1, char), replacement),
-                        Ordering::Greater => result
-                            .transition
-                            .insert((i - 1, char), *self.transition.get(&(i, char)).unwrap() - 1),
-                    };
+        for (&i, name) in self.names.iter().filter(|(&i, _)| i != state) {
+            result.names.insert(renumber(i), name.clone());
+        }
+        for i in (0..self.states).filter(|&i| i != state) {
+            result.accepting[renumber(i) as usize] = self.accepting[i as usize];
+            for char in &self.alphabet {
+                if let Some(&to) = self.transition.get(&(i, char.clone())) {
+                    result.transition.insert((renumber(i), char.clone()), renumber(to));
                 }
             }
         }
@@ -322,9 +633,10 @@ impl DFA {
                 }
                 checked.push(state);
                 new_checks = true;
-                for &char in &self.alphabet {
-                    if !reached.contains(self.transition.get(&(state, char)).unwrap()) {
-                        new_reached.push(*self.transition.get(&(state, char)).unwrap());
+                for char in &self.alphabet {
+                    match self.transition.get(&(state, char.clone())) {
+                        Some(&to) if !reached.contains(&to) => new_reached.push(to),
+                        _ => continue,
                     }
                 }
             }
@@ -334,22 +646,41 @@ impl DFA {
             }
         }
         reached.sort();
+        reached.dedup();
         reached
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.states == 0
+            || !self
+                .states_reachable_from(self.start)
+                .iter()
+                .any(|&i| self.accepting[i as usize])
+    }
+
     pub fn get_unreachable_states(&self) -> Vec<i32> {
-        let reached = self.states_reachable_from(0);
+        let reached = self.states_reachable_from(self.start);
         let mut result: Vec<i32> = (0..self.states).filter(|i| !reached.contains(i)).collect();
         result.reverse();
         result
     }
 
+    // a missing transition leads to the implicit dead state, so a state that can reach one is
+    // never permanently accepting
     fn is_permanently(&self, state: i32, accept: bool) -> bool {
         let states = self.states_reachable_from(state);
         for i in states {
             if self.accepting[i as usize] ^ accept {
                 return false;
             }
+            if accept
+                && self
+                    .alphabet
+                    .iter()
+                    .any(|c| !self.transition.contains_key(&(i, c.clone())))
+            {
+                return false;
+            }
         }
         true
     }
@@ -370,9 +701,7 @@ impl DFA {
         permanent.reverse();
         if permanent.len() > 1 {
             for i in 0..permanent.len() - 1 {
-                result = result
-                    .remove_state(permanent[i as usize], permanent[i as usize + 1])
-                    .unwrap();
+                result = result.remove_state(permanent[i], permanent[i + 1]).unwrap();
             }
         }
         result
@@ -392,9 +721,10 @@ impl DFA {
         //     result = result.remove_state(i, 0).unwrap();
         // }
         // result
-        self.get_unreachable_states()
-            .iter()
-            .fold(self.clone(), |dfa, state| dfa.remove_state(*state, 0).unwrap())
+        self.get_unreachable_states().iter().fold(self.clone(), |dfa, state| {
+            let start = dfa.start;
+            dfa.remove_state(*state, start).unwrap()
+        })
     }
 
     pub fn remove_indistinguishable_states(&self) -> Self {
@@ -419,18 +749,154 @@ impl DFA {
         if self.accepting[i as usize] != self.accepting[j as usize] {
             return false;
         }
-        for &char in &self.alphabet {
-            if self.transition.get(&(i, char)) == self.transition.get(&(j, char))
-                || self.transition.get(&(i, char)) == Some(&i) && self.transition.get(&(j, char)) == Some(&j)
-                || self.transition.get(&(i, char)) == Some(&j) && self.transition.get(&(j, char)) == Some(&i)
+        for char in &self.alphabet {
+            let from_i = self.transition.get(&(i, char.clone()));
+            let from_j = self.transition.get(&(j, char.clone()));
+            if from_i == from_j || from_i == Some(&i) && from_j == Some(&j) || from_i == Some(&j) && from_j == Some(&i)
             {
                 continue;
             }
+            // a missing transition is the same as one into a dead state
+            let dead = |to: Option<&i32>| to.is_none_or(|&to| self.is_permanently_rejecting(to));
+            if (from_i.is_none() || from_j.is_none()) && dead(from_i) && dead(from_j) {
+                continue;
+            }
             return false;
         }
         true
     }
 
+    pub(crate) fn from_nfa(alphabet: &[S], accepting: &[bool], edges: &[(i32, Option<S>, i32)]) -> Self {
+        let closure = |set: &mut Vec<i32>| {
+            let mut next = 0;
+            while next < set.len() {
+                for (from, char, to) in edges {
+                    if *from == set[next] && char.is_none() && !set.contains(to) {
+                        set.push(*to);
+                    }
+                }
+                next += 1;
+            }
+            set.sort();
+        };
+        let mut start = vec![0];
+        closure(&mut start);
+        let mut result = Self::new();
+        result.alphabet = alphabet.to_vec();
+        let mut index: HashMap<Vec<i32>, i32> = HashMap::new();
+        let mut queue = vec![start.clone()];
+        index.insert(start, 0);
+        let mut next = 0;
+        while next < queue.len() {
+            let set = queue[next].clone();
+            result.accepting.push(set.iter().any(|&i| accepting[i as usize]));
+            for char in alphabet {
+                let mut target = vec![];
+                for (from, c, to) in edges {
+                    if c.as_ref() == Some(char) && set.contains(from) && !target.contains(to) {
+                        target.push(*to);
+                    }
+                }
+                closure(&mut target);
+                let id = match index.get(&target) {
+                    Some(&id) => id,
+                    None => {
+                        let id = queue.len() as i32;
+                        index.insert(target.clone(), id);
+                        queue.push(target);
+                        id
+                    }
+                };
+                result.transition.insert((next as i32, char.clone()), id);
+            }
+            next += 1;
+        }
+        result.states = queue.len() as i32;
+        result.optimize()
+    }
+
+    // the same automaton started from `state` instead, i.e. accepting the strings that lead
+    // from `state` to an accepting state
+    pub fn with_start(&self, state: i32) -> Result<Self, ()> {
+        if state < 0 || state >= self.states {
+            return Err(());
+        }
+        let mut result = self.clone();
+        result.start = state;
+        Ok(result)
+    }
+
+    // the same automaton with the start state and state 0 swapped, for formats and machines
+    // that always start at state 0
+    pub fn start_at_zero(&self) -> Self {
+        if self.start == 0 {
+            return self.clone();
+        }
+        let swap = |state: i32| match state {
+            _ if state == self.start => 0,
+            0 => self.start,
+            _ => state,
+        };
+        let mut result = self.clone();
+        result.start = 0;
+        result.accepting.swap(0, self.start as usize);
+        result.transition = self
+            .transition
+            .iter()
+            .map(|((from, char), &to)| ((swap(*from), char.clone()), swap(to)))
+            .collect();
+        result.names = self.names.iter().map(|(&i, name)| (swap(i), name.clone())).collect();
+        result
+    }
+
+    pub(crate) fn missing_transitions(&self) -> bool {
+        (0..self.states).any(|i| {
+            self.alphabet
+                .iter()
+                .any(|c| !self.transition.contains_key(&(i, c.clone())))
+        })
+    }
+
+    // missing transitions are sent to a new rejecting sink state, if there are any
+    pub fn complete(&self) -> Self {
+        let mut result = self.clone();
+        result.partial = false;
+        if !self.missing_transitions() {
+            return result;
+        }
+        let sink = self.states;
+        result.states += 1;
+        result.accepting.push(false);
+        for from in 0..result.states {
+            for char in &self.alphabet {
+                result.transition.entry((from, char.clone())).or_insert(sink);
+            }
+        }
+        result
+    }
+
+    // the partial automaton without unreachable states, states that can never reach an accepting
+    // state, and the transitions into them. the start state is always kept
+    pub fn trim(&self) -> Self {
+        let reachable = match self.states {
+            0 => vec![],
+            _ => self.states_reachable_from(self.start),
+        };
+        let keep: Vec<bool> = (0..self.states)
+            .map(|i| i == self.start || reachable.contains(&i) && !self.is_permanently_rejecting(i))
+            .collect();
+        let mut result = self.clone();
+        result.partial = true;
+        result
+            .transition
+            .retain(|&(from, _), to| keep[from as usize] && keep[*to as usize]);
+        for i in (0..self.states).rev().filter(|&i| !keep[i as usize]) {
+            let start = result.start;
+            result = result.remove_state(i, start).unwrap();
+        }
+        result
+    }
+
     pub fn optimize(&self) -> Self {
         self.remove_unreachable_states()
             .reduce_accepting_states()
@@ -439,8 +905,51 @@ impl DFA {
     }
 }
 
-impl Display for DFA {
+impl<S: Symbol> Display for GenericDFA<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.to_string())
     }
 }
+
+pub struct Matches<'a> {
+    dfa: &'a DFA,
+    string: &'a str,
+    position: usize,
+    dead: Vec<bool>,
+}
+
+impl<'a> Iterator for Matches<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.dfa.states == 0 {
+            return None;
+        }
+        while self.position <= self.string.len() {
+            let start = self.position;
+            let next_char = self.string[start..].chars().next().map_or(1, |c| c.len_utf8());
+            match self.dfa.longest_match_at(self.string, start, &self.dead) {
+                Some(end) => {
+                    self.position = if end > start { end } else { start + next_char };
+                    return Some(start..end);
+                }
+                None => self.position += next_char,
+            }
+        }
+        None
+    }
+}
+
+impl Display for DFAFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use DFAFileError::*;
+        match self {
+            Io(e) => write!(f, "{e}"),
+            NotUtf8 => write!(f, "not a text or binary dfa file"),
+            Binary(e) => write!(f, "{e}"),
+            Json(e) => write!(f, "{e}"),
+            Jff(e) => write!(f, "{e}"),
+            Parse(e) => write!(f, "{e}"),
+        }
+    }
+}
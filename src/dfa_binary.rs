@@ -0,0 +1,237 @@
+use std::{
+    fmt::Display,
+    io::{self, Read, Write},
+};
+
+use crate::dfa::{DFAEvaluationError, DFA};
+
+pub const MAGIC: &[u8; 4] = b"DFA\0";
+pub const VERSION: u32 = 1;
+
+// no transition for this state and character
+const NONE: u32 = u32::MAX;
+
+const HEADER_LENGTH: usize = 16;
+
+#[derive(Debug)]
+pub enum BinaryError {
+    Io(String),
+    BadMagic,
+    UnsupportedVersion(u32),
+    Truncated,
+    ChecksumMismatch,
+    InvalidChar(u32),
+    InvalidState(u32),
+}
+
+// FNV-1a, over everything before the checksum
+fn checksum(bytes: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c9dc5;
+    for &byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+// layout, with every integer a little endian u32:
+//   magic "DFA\0", version, number of states, alphabet size
+//   the alphabet as unicode scalar values
+//   the transition table, one row per state and one column per alphabet character,
+//   holding the target state or 0xFFFFFFFF for none
+//   the accepting states as a bitset, lowest bit first, padded to a multiple of 4 bytes
+//   the checksum
+#[derive(Debug, Clone)]
+pub struct DfaView<'a> {
+    bytes: &'a [u8],
+    states: u32,
+    alphabet_size: u32,
+    // the alphabet sorted by character, with each character's column in the table
+    columns: Vec<(char, usize)>,
+}
+
+impl<'a> DfaView<'a> {
+    // checks the header, length, checksum and every table entry, so lookups afterwards
+    // never fail
+    pub fn new(bytes: &'a [u8]) -> Result<Self, BinaryError> {
+        use BinaryError::*;
+        if bytes.len() < 4 || &bytes[..4] != MAGIC {
+            return Err(BadMagic);
+        }
+        if bytes.len() < HEADER_LENGTH {
+            return Err(Truncated);
+        }
+        let version = u32_at(bytes, 4);
+        if version != VERSION {
+            return Err(UnsupportedVersion(version));
+        }
+        let states = u32_at(bytes, 8);
+        let alphabet_size = u32_at(bytes, 12);
+        let mut view = DfaView {
+            bytes,
+            states,
+            alphabet_size,
+            columns: vec![],
+        };
+        // computed without overflow before any offset is used
+        let (states_wide, alphabet_wide) = (states as u128, alphabet_size as u128);
+        let length = HEADER_LENGTH as u128
+            + 4 * alphabet_wide
+            + 4 * states_wide * alphabet_wide
+            + states_wide.div_ceil(32) * 4
+            + 4;
+        if bytes.len() as u128 != length {
+            return Err(Truncated);
+        }
+        let end = view.checksum_offset();
+        if checksum(&bytes[..end]) != u32_at(bytes, end) {
+            return Err(ChecksumMismatch);
+        }
+        for i in 0..alphabet_size as usize {
+            let code = u32_at(bytes, HEADER_LENGTH + 4 * i);
+            match char::from_u32(code) {
+                Some(char) => view.columns.push((char, i)),
+                None => return Err(InvalidChar(code)),
+            }
+        }
+        view.columns.sort();
+        view.columns.dedup_by_key(|&mut (c, _)| c);
+        for i in 0..states as usize * alphabet_size as usize {
+            let target = u32_at(bytes, view.table_offset() + 4 * i);
+            if target != NONE && target >= states {
+                return Err(InvalidState(target));
+            }
+        }
+        Ok(view)
+    }
+
+    fn table_offset(&self) -> usize {
+        HEADER_LENGTH + 4 * self.alphabet_size as usize
+    }
+
+    fn accepting_offset(&self) -> usize {
+        self.table_offset() + 4 * self.states as usize * self.alphabet_size as usize
+    }
+
+    fn checksum_offset(&self) -> usize {
+        self.accepting_offset() + (self.states as usize).div_ceil(32) * 4
+    }
+
+    pub fn states(&self) -> u32 {
+        self.states
+    }
+
+    pub fn alphabet(&self) -> impl Iterator<Item = char> + 'a {
+        let bytes = self.bytes;
+        (0..self.alphabet_size as usize).map(move |i| char::from_u32(u32_at(bytes, HEADER_LENGTH + 4 * i)).unwrap())
+    }
+
+    pub fn is_accepting(&self, state: u32) -> bool {
+        self.bytes[self.accepting_offset() + state as usize / 8] & (1 << (state % 8)) != 0
+    }
+
+    pub fn next(&self, state: u32, char: char) -> Result<u32, DFAEvaluationError> {
+        let column = match self.columns.binary_search_by_key(&char, |&(c, _)| c) {
+            Ok(i) => self.columns[i].1,
+            Err(_) => return Err(DFAEvaluationError::InvalidChar(char)),
+        };
+        let index = state as usize * self.alphabet_size as usize + column;
+        match u32_at(self.bytes, self.table_offset() + 4 * index) {
+            NONE => Err(DFAEvaluationError::NoTransition((state as i32, char))),
+            a => Ok(a),
+        }
+    }
+
+    pub fn evaluate(&self, string: &str) -> Result<bool, DFAEvaluationError> {
+        if self.states == 0 {
+            return Err(DFAEvaluationError::InvalidState(0));
+        }
+        let mut state = 0;
+        for char in string.chars() {
+            state = self.next(state, char)?;
+        }
+        Ok(self.is_accepting(state))
+    }
+
+    pub fn to_dfa(&self) -> DFA {
+        let mut result = DFA::new();
+        result.alphabet = self.alphabet().collect();
+        result.states = self.states as i32;
+        result.accepting = (0..self.states).map(|i| self.is_accepting(i)).collect();
+        for state in 0..self.states {
+            for &char in &result.alphabet {
+                if let Ok(to) = self.next(state, char) {
+                    result.transition.insert((state as i32, char), to as i32);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl DFA {
+    // the format has no start field, so the start state is swapped with state 0 first. it has no
+    // partial flag either, so a partial automaton is completed and reads back the same
+    pub fn to_binary(&self) -> Vec<u8> {
+        if self.start != 0 {
+            return self.start_at_zero().to_binary();
+        }
+        if self.partial {
+            return self.complete().to_binary();
+        }
+        let mut bytes = MAGIC.to_vec();
+        for value in [VERSION, self.states as u32, self.alphabet.len() as u32] {
+            bytes.extend(value.to_le_bytes());
+        }
+        for &char in &self.alphabet {
+            bytes.extend((char as u32).to_le_bytes());
+        }
+        for state in 0..self.states {
+            for char in &self.alphabet {
+                let target = match self.transition.get(&(state, *char)) {
+                    Some(&a) => a as u32,
+                    None => NONE,
+                };
+                bytes.extend(target.to_le_bytes());
+            }
+        }
+        let mut accepting = vec![0u8; (self.states as usize).div_ceil(32) * 4];
+        for state in (0..self.states as usize).filter(|&i| self.accepting[i]) {
+            accepting[state / 8] |= 1 << (state % 8);
+        }
+        bytes.extend(accepting);
+        bytes.extend(checksum(&bytes).to_le_bytes());
+        bytes
+    }
+
+    pub fn write_binary<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_binary())
+    }
+
+    pub fn read_binary<R: Read>(reader: &mut R) -> Result<Self, BinaryError> {
+        let mut bytes = vec![];
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| BinaryError::Io(e.to_string()))?;
+        Ok(DfaView::new(&bytes)?.to_dfa())
+    }
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use BinaryError::*;
+        match self {
+            Io(e) => write!(f, "{e}"),
+            BadMagic => write!(f, "not a binary dfa file"),
+            UnsupportedVersion(v) => write!(f, "unsupported binary dfa version {v}"),
+            Truncated => write!(f, "binary dfa file has the wrong length"),
+            ChecksumMismatch => write!(f, "binary dfa checksum does not match"),
+            InvalidChar(c) => write!(f, "invalid character code {c}"),
+            InvalidState(i) => write!(f, "invalid state: {i}"),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::dfa::DFA;
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl DFA {
+    // a Graphviz digraph of the state diagram. accepting states are drawn as double circles
+    // and the start state gets an arrow from an invisible node
+    pub fn to_dot(&self, hide_dead: bool) -> String {
+        let hidden = self.hidden_states(hide_dead);
+        let mut s = String::from("digraph dfa {\n    rankdir=LR;\n");
+        s.push_str("    node [shape=circle];\n");
+        s.push_str("    start [shape=none, label=\"\", width=0, height=0];\n");
+        for i in (0..self.states).filter(|&i| !hidden[i as usize]) {
+            let shape = match self.accepting[i as usize] {
+                true => "doublecircle",
+                false => "circle",
+            };
+            s.push_str(&format!("    {i} [shape={shape}];\n"));
+        }
+        if self.states > 0 {
+            s.push_str(&format!("    start -> {};\n", self.start));
+        }
+        for (from, to, chars) in self.merged_edges(hide_dead) {
+            let label: Vec<String> = chars.iter().map(|c| c.to_string()).collect();
+            s.push_str(&format!(
+                "    {from} -> {to} [label=\"{}\"];\n",
+                escape(&label.join(","))
+            ));
+        }
+        s.push_str("}\n");
+        s
+    }
+}
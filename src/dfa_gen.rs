@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
 
 use crate::dfa::*;
 
@@ -7,7 +7,10 @@ pub fn empty(alph: &[char]) -> DFA {
         states: 1,
         alphabet: alph.to_vec(),
         accepting: vec![false],
+        start: 0,
         transition: HashMap::new(),
+        names: HashMap::new(),
+        partial: false,
     };
     for &char in alph {
         result.transition.insert((0, char), 0);
@@ -20,7 +23,10 @@ pub fn all_strings(alph: &[char]) -> DFA {
         states: 1,
         alphabet: alph.to_vec(),
         accepting: vec![true],
+        start: 0,
         transition: HashMap::new(),
+        names: HashMap::new(),
+        partial: false,
     };
     for &char in alph {
         result.transition.insert((0, char), 0);
@@ -36,7 +42,10 @@ pub fn modulo_n(alph: &[char], chars: &[char], accept: i32, n: i32) -> Result<DF
         states: n,
         alphabet: alph.to_vec(),
         accepting: vec![false; n as usize],
+        start: 0,
         transition: HashMap::new(),
+        names: HashMap::new(),
+        partial: false,
     };
     result.accepting[accept as usize] = true;
     for i in 0..n {
@@ -59,7 +68,10 @@ pub fn exact_length(alph: &[char], chars: &[char], n: i32) -> Result<DFA, ()> {
         states: n + 2,
         alphabet: alph.to_vec(),
         accepting: vec![false; n as usize + 2],
+        start: 0,
         transition: HashMap::new(),
+        names: HashMap::new(),
+        partial: false,
     };
     result.accepting[n as usize] = true;
     for i in 0..=n {
@@ -85,7 +97,10 @@ pub fn length_or_less(alph: &[char], chars: &[char], n: i32) -> Result<DFA, ()>
         states: n + 2,
         alphabet: alph.to_vec(),
         accepting: vec![false; n as usize + 2],
+        start: 0,
         transition: HashMap::new(),
+        names: HashMap::new(),
+        partial: false,
     };
     for i in 0..=n {
         result.accepting[i as usize] = true;
@@ -116,7 +131,10 @@ pub fn only_string(alph: &[char], string: &str) -> Result<DFA, ()> {
         states: length + 2,
         alphabet: alph.to_vec(),
         accepting: vec![false; length as usize + 2],
+        start: 0,
         transition: HashMap::new(),
+        names: HashMap::new(),
+        partial: false,
     };
     result.accepting[length as usize] = true;
     for i in 0..length + 2 {
@@ -130,6 +148,115 @@ pub fn only_string(alph: &[char], string: &str) -> Result<DFA, ()> {
     Ok(result)
 }
 
+// Daciuk's incremental construction of the minimal acyclic automaton. words are added in
+// lexicographic order, so only the path of the previous word can still change and everything
+// left of it is merged into the register of finished states as soon as it is passed.
+// unsorted input is sorted first
+pub fn from_words(alph: &[char], words: &[&str]) -> Result<DFA, ()> {
+    for char in words.iter().flat_map(|w| w.chars()) {
+        if !alph.contains(&char) {
+            return Err(());
+        }
+    }
+    let mut words = words.to_vec();
+    words.sort();
+    words.dedup();
+    if words.is_empty() {
+        return Ok(empty(alph));
+    }
+    let mut builder = WordListBuilder {
+        states: vec![(false, BTreeMap::new())],
+        register: HashMap::new(),
+    };
+    for word in words {
+        let mut state = 0;
+        let mut rest = word.chars();
+        for char in word.chars() {
+            match builder.states[state].1.get(&char) {
+                Some(&next) => {
+                    state = next;
+                    rest.next();
+                }
+                None => break,
+            }
+        }
+        if !builder.states[state].1.is_empty() {
+            builder.replace_or_register(state);
+        }
+        for char in rest {
+            builder.states.push((false, BTreeMap::new()));
+            let next = builder.states.len() - 1;
+            builder.states[state].1.insert(char, next);
+            state = next;
+        }
+        builder.states[state].0 = true;
+    }
+    // with only the empty word the root has no path left to finish
+    if !builder.states[0].1.is_empty() {
+        builder.replace_or_register(0);
+    }
+
+    let mut index = HashMap::from([(0, 0)]);
+    let mut queue = vec![0];
+    let mut next = 0;
+    while next < queue.len() {
+        for &target in builder.states[queue[next]].1.values() {
+            if let Entry::Vacant(entry) = index.entry(target) {
+                entry.insert(queue.len() as i32);
+                queue.push(target);
+            }
+        }
+        next += 1;
+    }
+    let sink = queue.len() as i32;
+    let mut result = DFA {
+        states: sink + 1,
+        alphabet: alph.to_vec(),
+        accepting: queue.iter().map(|&s| builder.states[s].0).collect(),
+        start: 0,
+        transition: HashMap::new(),
+        names: HashMap::new(),
+        partial: false,
+    };
+    result.accepting.push(false);
+    for (i, &state) in queue.iter().enumerate() {
+        for &char in alph {
+            let target = match builder.states[state].1.get(&char) {
+                Some(t) => index[t],
+                None => sink,
+            };
+            result.transition.insert((i as i32, char), target);
+        }
+    }
+    for &char in alph {
+        result.transition.insert((sink, char), sink);
+    }
+    Ok(result)
+}
+
+struct WordListBuilder {
+    states: Vec<(bool, BTreeMap<char, usize>)>,
+    register: HashMap<(bool, BTreeMap<char, usize>), usize>,
+}
+
+impl WordListBuilder {
+    // the last child of `state` lies on the path of the previous word and is now finished
+    fn replace_or_register(&mut self, state: usize) {
+        let (&char, &child) = self.states[state].1.iter().next_back().unwrap();
+        if !self.states[child].1.is_empty() {
+            self.replace_or_register(child);
+        }
+        match self.register.get(&self.states[child]) {
+            Some(&existing) => {
+                self.states[state].1.insert(char, existing);
+            }
+            None => {
+                self.register.insert(self.states[child].clone(), child);
+            }
+        }
+    }
+}
+
 pub fn begins_with(alph: &[char], string: &str) -> Result<DFA, ()> {
     for char in string.chars() {
         if !alph.contains(&char) {
@@ -141,7 +268,10 @@ pub fn begins_with(alph: &[char], string: &str) -> Result<DFA, ()> {
         states: length + 2,
         alphabet: alph.to_vec(),
         accepting: vec![false; length as usize + 2],
+        start: 0,
         transition: HashMap::new(),
+        names: HashMap::new(),
+        partial: false,
     };
     result.accepting[length as usize] = true;
     for i in 0..length + 2 {
@@ -169,7 +299,10 @@ pub fn ends_wtih(alph: &[char], string: &str) -> Result<DFA, ()> {
         states: length + 1,
         alphabet: alph.to_vec(),
         accepting: vec![false; length as usize + 1],
+        start: 0,
         transition: HashMap::new(),
+        names: HashMap::new(),
+        partial: false,
     };
     result.accepting[length as usize] = true;
     for i in 0..length + 1 {
@@ -196,7 +329,10 @@ pub fn contains_substring(alph: &[char], string: &str) -> Result<DFA, ()> {
         states: length + 1,
         alphabet: alph.to_vec(),
         accepting: vec![false; length as usize + 1],
+        start: 0,
         transition: HashMap::new(),
+        names: HashMap::new(),
+        partial: false,
     };
     result.accepting[length as usize] = true;
     for i in 0..length + 1 {
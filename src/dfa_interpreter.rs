@@ -1,8 +1,23 @@
-use std::{fs::File, io::Write};
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+};
 
 use itertools::Itertools;
 
-use crate::{dfa::DFA, dfa_gen};
+use crate::{
+    buchi::Buchi,
+    cfg::{Grammar, GrammarError},
+    codegen,
+    dfa::DFA,
+    dfa_gen,
+    learn::{self, CommandOracle, MergeHeuristic, RandomSampling, WMethod},
+    lexer::{LexError, Lexer},
+    pda::PDA,
+    turing::TuringMachine,
+    two_way_dfa::TwoWayDFA,
+};
 
 pub fn dfa_interpreter(args: Vec<String>) -> i32 {
     if args.len() < 2 {
@@ -11,42 +26,442 @@ pub fn dfa_interpreter(args: Vec<String>) -> i32 {
     }
     match args[1].as_str() {
         "eval" | "evaluate" => {
+            let trace = args.iter().any(|s| s == "--trace");
+            let partial = args.iter().any(|s| s == "--partial");
+            let args: Vec<String> = args
+                .iter()
+                .filter(|s| *s != "--trace" && *s != "--partial")
+                .cloned()
+                .collect();
             if args.len() < 3 {
-                println!("Correct Syntax: evaluate <dfa> <string> [string] ...");
+                println!("Correct Syntax: evaluate [--trace] [--partial] <dfa> <string> [string] ...");
+                return 1;
+            }
+            let evaluate = match evaluator(&args[2], trace, partial) {
+                Ok(a) => a,
+                Err(e) => {
+                    println!("{e}");
+                    return 1;
+                }
+            };
+            if args.len() > 3 {
+                for s in &args[3..] {
+                    if trace {
+                        println!("{s}:\n{}\n", evaluate(s));
+                    } else {
+                        println!("{s}: {}", evaluate(s));
+                    }
+                }
+            } else if trace {
+                println!(" :\n{}", evaluate(""));
+            } else {
+                println!(" : {}", evaluate(""));
+            }
+        }
+
+        "grep" | "search" => {
+            if args.len() != 4 {
+                println!("Correct Syntax: grep <dfa> <file>");
+                return 1;
+            }
+            let dfa = match DFA::open_dfa_file(&args[2]) {
+                Ok(a) => a,
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[2]);
+                    return 1;
+                }
+            };
+            let mut contents = String::new();
+            if File::open(&args[3])
+                .and_then(|mut file| file.read_to_string(&mut contents))
+                .is_err()
+            {
+                println!("unable to open file {}", args[3]);
+                return 1;
+            }
+            for (i, line) in contents.lines().enumerate() {
+                let spans = dfa.find_iter(line).map(|r| format!("{}..{}", r.start, r.end)).join(",");
+                if !spans.is_empty() {
+                    println!("{}:{spans}: {line}", i + 1);
+                }
+            }
+        }
+
+        "tokenize" | "lex" => {
+            if args.len() != 4 {
+                println!("Correct Syntax: tokenize <spec> <file>");
+                return 1;
+            }
+            let lexer = match Lexer::open_spec_file(&args[2]) {
+                Ok(a) => a,
+                Err(LexError::UnableToOpen(path)) => {
+                    println!("unable to open file {path}");
+                    return 1;
+                }
+                Err(LexError::InvalidSpecLine(line)) => {
+                    println!("invalid token spec on line {line} of {}", args[2]);
+                    return 1;
+                }
+                Err(e) => {
+                    println!("{e}");
+                    return 1;
+                }
+            };
+            let mut contents = String::new();
+            if File::open(&args[3])
+                .and_then(|mut file| file.read_to_string(&mut contents))
+                .is_err()
+            {
+                println!("unable to open file {}", args[3]);
                 return 1;
             }
-            if let Ok(dfa) = DFA::open_dfa_file(&args[2]) {
-                if args.len() > 3 {
-                    for i in 3..args.len() {
-                        println!("{}: {}", &args[i], dfa.evaluate_to_string(&args[i]));
+            match lexer.tokenize(&contents) {
+                Ok(tokens) => {
+                    for (name, range) in tokens {
+                        println!("{name}\t{}..{}\t{:?}", range.start, range.end, &contents[range.clone()]);
                     }
+                }
+                Err(LexError::Untokenizable(position)) => {
+                    println!("untokenizable input at byte {position}");
+                    return 1;
+                }
+                Err(e) => {
+                    println!("{e}");
+                    return 1;
+                }
+            }
+        }
+
+        "cfg-intersect" | "intersect-cfg" => {
+            if args.len() < 4 || args.len() > 5 {
+                println!("Correct Syntax: cfg-intersect <grammar> <dfa> [outfilename]");
+                return 1;
+            }
+            let grammar = match Grammar::open_grammar_file(&args[2]) {
+                Ok(a) => a,
+                Err(GrammarError::InvalidLine(line)) => {
+                    println!("invalid grammar rule on line {line} of {}", args[2]);
+                    return 1;
+                }
+                Err(GrammarError::NoRules) => {
+                    println!("grammar {} has no rules", args[2]);
+                    return 1;
+                }
+                Err(GrammarError::UnableToOpen(path)) => {
+                    println!("unable to open file {path}");
+                    return 1;
+                }
+            };
+            let dfa = match DFA::open_dfa_file(&args[3]) {
+                Ok(a) => a,
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[3]);
+                    return 1;
+                }
+            };
+            let intersection = grammar.intersect_dfa(&dfa);
+            match intersection.witness() {
+                Some(w) => println!("nonempty, for example: {w}"),
+                None => println!("empty"),
+            }
+            if args.len() == 5 {
+                if let Ok(mut file) = File::create(&args[4]) {
+                    file.write_all(intersection.to_string().as_bytes()).unwrap();
                 } else {
-                    println!(" : {}", dfa.evaluate_to_string(""));
+                    println!("error creating file {}", args[4]);
+                    return 1;
+                };
+            }
+        }
+
+        "dot" => {
+            let hide_dead = args.iter().any(|s| s == "--hide-dead");
+            let args: Vec<String> = args.iter().filter(|s| *s != "--hide-dead").cloned().collect();
+            if args.len() < 3 || args.len() > 4 {
+                println!("Correct Syntax: dot [--hide-dead] <dfa> [outfilename]");
+                return 1;
+            }
+            let dfa = match DFA::open_dfa_file(&args[2]) {
+                Ok(a) => a,
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[2]);
+                    return 1;
                 }
+            };
+            let dot = dfa.to_dot(hide_dead);
+            if args.len() == 3 {
+                print!("{dot}");
+            } else if let Ok(mut file) = File::create(&args[3]) {
+                file.write_all(dot.as_bytes()).unwrap();
             } else {
-                println!("unable to open file {}", args[2]);
+                println!("error creating file {}", args[3]);
                 return 1;
             }
         }
 
-        "negate" | "negation" => {
-            if args.len() < 4 {
-                println!("Correct Syntax: negate <outfilename> <dfa>");
+        "tikz" => {
+            let standalone = args.iter().any(|s| s == "--standalone");
+            let args: Vec<String> = args.iter().filter(|s| *s != "--standalone").cloned().collect();
+            if args.len() < 3 || args.len() > 4 {
+                println!("Correct Syntax: tikz [--standalone] <dfa> [outfilename]");
+                return 1;
+            }
+            let dfa = match DFA::open_dfa_file(&args[2]) {
+                Ok(a) => a,
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[2]);
+                    return 1;
+                }
+            };
+            let tikz = match standalone {
+                true => dfa.to_tikz_document(),
+                false => dfa.to_tikz(),
+            };
+            if args.len() == 3 {
+                print!("{tikz}");
+            } else if let Ok(mut file) = File::create(&args[3]) {
+                file.write_all(tikz.as_bytes()).unwrap();
+            } else {
+                println!("error creating file {}", args[3]);
+                return 1;
+            }
+        }
+
+        "svg" => {
+            if args.len() != 4 {
+                println!("Correct Syntax: svg <dfa> <outfilename>");
+                return 1;
+            }
+            let dfa = match DFA::open_dfa_file(&args[2]) {
+                Ok(a) => a,
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[2]);
+                    return 1;
+                }
+            };
+            let contents = match args[3].ends_with(".html") {
+                true => dfa.to_html_report(),
+                false => dfa.to_svg(),
+            };
+            if let Ok(mut file) = File::create(&args[3]) {
+                file.write_all(contents.as_bytes()).unwrap();
+            } else {
+                println!("error creating file {}", args[3]);
+                return 1;
+            }
+        }
+
+        "info" => {
+            if args.len() != 3 {
+                println!("Correct Syntax: info <dfa>");
+                return 1;
+            }
+            match DFA::open_dfa_file(&args[2]) {
+                Ok(dfa) => {
+                    for (name, value) in dfa.info() {
+                        println!("{name}: {value}");
+                    }
+                }
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[2]);
+                    return 1;
+                }
+            }
+        }
+
+        "upgrade" => {
+            if args.len() != 4 {
+                println!("Correct Syntax: upgrade <outfilename> <dfa>");
+                return 1;
+            }
+            let dfa = match DFA::open_dfa_file(&args[3]) {
+                Ok(a) => a,
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[3]);
+                    return 1;
+                }
+            };
+            if let Ok(mut file) = File::create(&args[2]) {
+                file.write_all(dfa.to_v2_string().as_bytes()).unwrap();
+            } else {
+                println!("error creating file {}", args[2]);
                 return 1;
             }
-            if let Ok(dfa) = DFA::open_dfa_file(&args[3]) {
-                if let Ok(mut file) = File::create(&args[2]) {
-                    file.write_all(dfa.negation().to_string().as_bytes()).unwrap();
+        }
+
+        "codegen" => {
+            let backend = match args.get(2).and_then(|s| codegen::backend(s)) {
+                Some(a) if args.len() == 5 => a,
+                _ => {
+                    println!("Correct Syntax: codegen <rust|c|python|javascript> <dfa> <outfilename>");
+                    return 1;
+                }
+            };
+            let dfa = match DFA::open_dfa_file(&args[3]) {
+                Ok(a) => a,
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[3]);
+                    return 1;
+                }
+            };
+            let path = Path::new(&args[4]);
+            let name = backend.function_name(&path.file_stem().unwrap_or_default().to_string_lossy());
+            // the main file goes to the given path and any others next to it, named after the
+            // function like the C header that the source includes
+            for (i, (extension, contents)) in backend.generate(&dfa, &name).iter().enumerate() {
+                let file_path = match i {
+                    0 => path.to_path_buf(),
+                    _ => path.with_file_name(format!("{name}.{extension}")),
+                };
+                if let Ok(mut file) = File::create(&file_path) {
+                    file.write_all(contents.as_bytes()).unwrap();
                 } else {
+                    println!("error creating file {}", file_path.display());
+                    return 1;
+                }
+            }
+        }
+
+        "2dfa-to-dfa" => {
+            if args.len() != 4 {
+                println!("Correct Syntax: 2dfa-to-dfa <outfilename> <2dfa>");
+                return 1;
+            }
+            if let Ok(two_way) = TwoWayDFA::open_2dfa_file(&args[3]) {
+                if two_way.to_dfa().save_dfa_file(&args[2]).is_err() {
                     println!("error creating file {}", args[2]);
                     return 1;
-                };
+                }
             } else {
                 println!("unable to open file {}", args[3]);
                 return 1;
             }
         }
 
+        "learn" => {
+            let separator = args.iter().position(|s| s == "--");
+            let (out, alph, command) = match separator {
+                Some(4) if args.len() > 5 => (&args[2], &args[3], args[5..].to_vec()),
+                _ => {
+                    println!("Correct Syntax: learn <outfilename> <alph> -- <command> [args] ...");
+                    return 1;
+                }
+            };
+            let alph: Vec<char> = alph.split(',').map(|s| s.chars().next().unwrap_or(' ')).collect();
+            let mut membership = CommandOracle::new(command);
+            let mut equivalence = (
+                WMethod { extra_states: 1 },
+                RandomSampling {
+                    samples: 1000,
+                    max_length: 20,
+                    seed: 1,
+                },
+            );
+            let dfa = learn::lstar(&alph, &mut membership, &mut equivalence);
+            if let Some(e) = membership.error {
+                println!("{e}");
+                return 1;
+            }
+            if dfa.save_dfa_file(out).is_err() {
+                println!("error creating file {out}");
+                return 1;
+            }
+        }
+
+        "learn-from" => {
+            let heuristic = match args.iter().any(|s| s == "--edsm") {
+                true => MergeHeuristic::Edsm,
+                false => MergeHeuristic::Rpni,
+            };
+            let args: Vec<String> = args.iter().filter(|s| *s != "--edsm").cloned().collect();
+            if args.len() != 4 {
+                println!("Correct Syntax: learn-from [--edsm] <outfilename> <samples>");
+                return 1;
+            }
+            let mut contents = String::new();
+            if File::open(&args[3])
+                .and_then(|mut file| file.read_to_string(&mut contents))
+                .is_err()
+            {
+                println!("unable to open file {}", args[3]);
+                return 1;
+            }
+            let (mut positive, mut negative, mut alph) = (vec![], vec![], vec![]);
+            for (i, line) in contents.lines().enumerate() {
+                let line = line.trim_end_matches('\r');
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let sample = if let Some(s) = line.strip_prefix('+') {
+                    positive.push(s);
+                    s
+                } else if let Some(s) = line.strip_prefix('-') {
+                    negative.push(s);
+                    s
+                } else {
+                    println!("line {} of {} does not start with + or -", i + 1, args[3]);
+                    return 1;
+                };
+                for char in sample.chars() {
+                    if !alph.contains(&char) {
+                        alph.push(char);
+                    }
+                }
+            }
+            let dfa = match learn::passive(&positive, &negative, &alph, heuristic) {
+                Ok(a) => a,
+                Err(s) => {
+                    println!("{s:?} is both a positive and a negative sample in {}", args[3]);
+                    return 1;
+                }
+            };
+            if dfa.save_dfa_file(&args[2]).is_err() {
+                println!("error creating file {}", args[2]);
+                return 1;
+            }
+        }
+
+        "negate" | "negation" => {
+            if args.len() < 4 {
+                println!("Correct Syntax: negate <outfilename> <dfa>");
+                return 1;
+            }
+            match DFA::open_dfa_file(&args[3]) {
+                Ok(dfa) => {
+                    if dfa.negation().save_dfa_file(&args[2]).is_err() {
+                        println!("error creating file {}", args[2]);
+                        return 1;
+                    }
+                }
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[3]);
+                    return 1;
+                }
+            }
+        }
+
+        "complete" | "trim" => {
+            if args.len() != 4 {
+                println!("Correct Syntax: {} <outfilename> <dfa>", args[1]);
+                return 1;
+            }
+            let dfa = match DFA::open_dfa_file(&args[3]) {
+                Ok(a) => a,
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[3]);
+                    return 1;
+                }
+            };
+            let result = match args[1].as_str() {
+                "complete" => dfa.complete(),
+                _ => dfa.trim(),
+            };
+            if result.save_dfa_file(&args[2]).is_err() {
+                println!("error creating file {}", args[2]);
+                return 1;
+            }
+        }
+
         "and" | "intersect" => {
             if args.len() < 5 {
                 println!("Correct Syntax: intersect <outfilename> <dfa1> <dfa2> [dfa3] ...");
@@ -56,25 +471,20 @@ pub fn dfa_interpreter(args: Vec<String>) -> i32 {
             let dfas: Vec<DFA> = args[3..args.len()]
                 .iter()
                 .map(|s| {
-                    if let Ok(dfa) = DFA::open_dfa_file(s) {
-                        dfa
-                    } else {
-                        println!("unable to open file {}", args[3]);
+                    DFA::open_dfa_file(s).unwrap_or_else(|e| {
+                        println!("unable to open file {s}: {e}");
                         exit = true;
                         DFA::new()
-                    }
+                    })
                 })
                 .collect_vec();
             if exit {
                 return 1;
             }
-            if let Ok(mut file) = File::create(&args[2]) {
-                file.write_all(DFA::big_intersect(&dfas).to_string().as_bytes())
-                    .unwrap();
-            } else {
+            if DFA::big_intersect(&dfas).save_dfa_file(&args[2]).is_err() {
                 println!("error creating file {}", args[2]);
                 return 1;
-            };
+            }
         }
 
         "or" | "union" => {
@@ -86,24 +496,20 @@ pub fn dfa_interpreter(args: Vec<String>) -> i32 {
             let dfas: Vec<DFA> = args[3..args.len()]
                 .iter()
                 .map(|s| {
-                    if let Ok(dfa) = DFA::open_dfa_file(s) {
-                        dfa
-                    } else {
-                        println!("unable to open file {}", args[3]);
+                    DFA::open_dfa_file(s).unwrap_or_else(|e| {
+                        println!("unable to open file {s}: {e}");
                         exit = true;
                         DFA::new()
-                    }
+                    })
                 })
                 .collect_vec();
             if exit {
                 return 1;
             }
-            if let Ok(mut file) = File::create(&args[2]) {
-                file.write_all(DFA::big_union(&dfas).to_string().as_bytes()).unwrap();
-            } else {
+            if DFA::big_union(&dfas).save_dfa_file(&args[2]).is_err() {
                 println!("error creating file {}", args[2]);
                 return 1;
-            };
+            }
         }
 
         "difference" => {
@@ -113,34 +519,30 @@ pub fn dfa_interpreter(args: Vec<String>) -> i32 {
             }
             let lhs = match DFA::open_dfa_file(&args[3]) {
                 Ok(a) => a,
-                Err(_) => {
-                    println!("unable to open file {}", args[3]);
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[3]);
                     return 1;
                 }
             };
             let rhs = match DFA::open_dfa_file(&args[4]) {
                 Ok(a) => a,
-                Err(_) => {
-                    println!("unable to open file {}", args[4]);
+                Err(e) => {
+                    println!("unable to open file {}: {e}", args[4]);
                     return 1;
                 }
             };
-            if let Ok(mut file) = File::create(&args[2]) {
-                file.write_all(lhs.difference(&rhs).to_string().as_bytes()).unwrap();
-            } else {
+            if lhs.difference(&rhs).save_dfa_file(&args[2]).is_err() {
                 println!("error creating file {}", args[2]);
                 return 1;
-            };
+            }
         }
 
         "gen" | "generate" => {
             if let Some(dfa) = generate(&args) {
-                if let Ok(mut file) = File::create(&args[3]) {
-                    file.write_all(dfa.to_string().as_bytes()).unwrap();
-                } else {
+                if dfa.save_dfa_file(&args[3]).is_err() {
                     println!("error creating file {}", args[2]);
                     return 1;
-                };
+                }
             } else {
                 _gen_help()
             }
@@ -154,6 +556,44 @@ pub fn dfa_interpreter(args: Vec<String>) -> i32 {
     0
 }
 
+type Evaluator = Box<dyn Fn(&str) -> String>;
+
+// `partial` makes missing transitions of a dfa reject the string instead of being an error
+fn evaluator(path: &str, trace: bool, partial: bool) -> Result<Evaluator, String> {
+    if path.ends_with(".cfg") {
+        let grammar = Grammar::open_grammar_file(path).map_err(|_| format!("unable to open file {path}"))?;
+        Ok(Box::new(move |s| grammar.earley(s).to_string()))
+    } else if path.ends_with(".buchi") {
+        let buchi = Buchi::open_buchi_file(path).map_err(|_| format!("unable to open file {path}"))?;
+        Ok(Box::new(move |s| buchi.evaluate_to_string(s)))
+    } else if path.ends_with(".2dfa") {
+        let two_way = TwoWayDFA::open_2dfa_file(path).map_err(|_| format!("unable to open file {path}"))?;
+        Ok(Box::new(move |s| two_way.evaluate_to_string(s)))
+    } else if path.ends_with(".tm") {
+        let tm = TuringMachine::open_tm_file(path).map_err(|_| format!("unable to open file {path}"))?;
+        if trace {
+            Ok(Box::new(move |s| tm.trace_to_string(s)))
+        } else {
+            Ok(Box::new(move |s| tm.evaluate_to_string(s)))
+        }
+    } else if path.ends_with(".pda") {
+        let pda = PDA::open_pda_file(path).map_err(|_| format!("unable to open file {path}"))?;
+        if trace {
+            Ok(Box::new(move |s| pda.trace_to_string(s)))
+        } else {
+            Ok(Box::new(move |s| pda.evaluate_to_string(s)))
+        }
+    } else {
+        let mut dfa = DFA::open_dfa_file(path).map_err(|e| format!("unable to open file {path}: {e}"))?;
+        dfa.partial |= partial;
+        if trace {
+            Ok(Box::new(move |s| dfa.trace_to_string(s)))
+        } else {
+            Ok(Box::new(move |s| dfa.evaluate_to_string(s)))
+        }
+    }
+}
+
 fn generate(args: &[String]) -> Option<DFA> {
     if args.len() < 5 {
         return None;
@@ -204,6 +644,19 @@ fn generate(args: &[String]) -> Option<DFA> {
             }
             Some(dfa_gen::only_string(&alph, &args[5]).unwrap())
         }
+        "words" | "word_list" => {
+            if args.len() < 6 {
+                return None;
+            }
+            let mut contents = String::new();
+            File::open(&args[5]).ok()?.read_to_string(&mut contents).ok()?;
+            let words: Vec<&str> = contents
+                .lines()
+                .map(|s| s.trim_end_matches('\r'))
+                .filter(|s| !s.is_empty())
+                .collect();
+            dfa_gen::from_words(&alph, &words).ok()
+        }
         "begins" | "begins_with" | "starts" | "starts_wtih" => {
             if args.len() < 6 {
                 return None;
@@ -227,15 +680,55 @@ fn generate(args: &[String]) -> Option<DFA> {
 }
 
 fn help() {
-    println!("to evaluate a string in an automaton:");
-    println!("evaluate <dfa> <string> [string] ...");
-    println!("");
+    println!(
+        "dfa files ending in .json or .jff are read and written as json or JFLAP files instead of the .dfa format"
+    );
+    println!(
+        "a - in place of a target state in a .dfa file is a missing transition, which is an error when evaluating"
+    );
+    println!("unless --partial is given, or the dfa is a partial json or v2 file");
+    println!();
+    println!("to evaluate a string in an automaton or grammar (.dfa, .2dfa, .pda, .tm, .buchi or .cfg):");
+    println!("infinite words for .buchi files are written as u(v), meaning u followed by v repeated forever");
+    println!("evaluate [--trace] [--partial] <dfa> <string> [string] ...");
+    println!("to search a text file for substrings accepted by an automaton:");
+    println!("grep <dfa> <file>");
+    println!();
+    println!("to draw a dfa as a graphviz digraph, optionally leaving out the dead state:");
+    println!("dot [--hide-dead] <dfa> [outfilename]");
+    println!("to draw a dfa as a tikzpicture, optionally as a complete LaTeX document:");
+    println!("tikz [--standalone] <dfa> [outfilename]");
+    println!("to draw a dfa as an svg image, or as an html report with its table and statistics if the output ends in .html:");
+    println!("svg <dfa> <outfilename>");
+    println!("to generate a standalone function named after the output file that evaluates a dfa");
+    println!("(c also writes a header next to the source file):");
+    println!("codegen <rust|c|python|javascript> <dfa> <outfilename>");
+    println!("to print statistics about a dfa:");
+    println!("info <dfa>");
+    println!("to rewrite a dfa in version 2 of the .dfa format, with named states and quoted symbols:");
+    println!("upgrade <outfilename> <dfa>");
+    println!();
+    println!("to split a text file into tokens (spec lines are '<token name> <dfa>'):");
+    println!("tokenize <spec> <file>");
+    println!();
+    println!("to check whether a grammar generates any string accepted by an automaton:");
+    println!("cfg-intersect <grammar> <dfa> [outfilename]");
+    println!();
     println!("to create a new automaton from existing files:");
     println!("negate <outfilename> <dfa>");
     println!("intersect <outfilename> <dfa1> <dfa2> [dfa3] ...");
     println!("union <outfilename> <dfa1> <dfa2> [dfa3] ...");
     println!("difference <outfilename> <dfa1> <dfa2>");
-    println!("");
+    println!("2dfa-to-dfa <outfilename> <2dfa>");
+    println!("to add a rejecting sink state for missing transitions, or remove every state that cannot accept:");
+    println!("complete <outfilename> <dfa>");
+    println!("trim <outfilename> <dfa>");
+    println!();
+    println!("to learn an automaton from a program that exits successfully on accepted input:");
+    println!("learn <outfilename> <alph> -- <command> [args] ...");
+    println!("to infer an automaton from sample lines of the form +accepted or -rejected:");
+    println!("learn-from [--edsm] <outfilename> <samples>");
+    println!();
     println!("to generate an automaton from presets:");
     println!("gen <preset> <outfilename> <args...>");
     println!("use 'gen help' to see list of presets.");
@@ -251,6 +744,7 @@ fn _gen_help() {
     );
     println!("strings with number of chars congruent to i modulo j: gen mod <outfilename> <alph> <chars> <i> <j>");
     println!("only one string: gen only <outfilename> <alph> <string>");
+    println!("any word in a file with one word per line: gen words <outfilename> <alph> <wordfile>");
     println!("begins with a certain substring: gen begins <outfilename> <alph> <string>");
     println!("ends with a certain substring: gen ends <outfilename> <alph> <string>");
     println!("contains a certain substring: gen contains <outfilename> <alph> <string>");
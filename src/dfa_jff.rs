@@ -0,0 +1,137 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::dfa::DFA;
+
+#[derive(Debug)]
+pub enum JffError {
+    Xml(String),
+    NotFiniteAutomaton(String),
+    NoInitialState,
+    MultipleInitialStates,
+    InvalidState(String),
+    EmptyTransition(String),
+    MultiCharacterSymbol(String),
+    Nondeterministic((String, char)),
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn child_text<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|n| n.has_tag_name(name))
+        .map(|n| n.text().unwrap_or(""))
+}
+
+impl DFA {
+    // JFLAP state ids are renumbered in document order with the initial state moved to 0.
+    // the alphabet is every symbol read by a transition, in order of first appearance.
+    // JFLAP allows missing transitions, so a rejecting sink state is added if any are missing
+    pub fn from_jff(s: &str) -> Result<Self, JffError> {
+        use JffError::*;
+        let document = roxmltree::Document::parse(s).map_err(|e| Xml(e.to_string()))?;
+        let root = document.root_element();
+        let kind = child_text(root, "type").unwrap_or("").trim();
+        if kind != "fa" {
+            return Err(NotFiniteAutomaton(kind.to_string()));
+        }
+        let automaton = root.children().find(|n| n.has_tag_name("automaton")).unwrap_or(root);
+        let states: Vec<roxmltree::Node> = automaton.children().filter(|n| n.has_tag_name("state")).collect();
+        let initial: Vec<usize> = (0..states.len())
+            .filter(|&i| states[i].children().any(|n| n.has_tag_name("initial")))
+            .collect();
+        let initial = match initial[..] {
+            [] => return Err(NoInitialState),
+            [a] => a,
+            _ => return Err(MultipleInitialStates),
+        };
+        let mut order = vec![initial];
+        order.extend((0..states.len()).filter(|&i| i != initial));
+        let mut ids: HashMap<&str, i32> = HashMap::new();
+        let mut result = DFA::new();
+        result.states = states.len() as i32;
+        for (new, &old) in order.iter().enumerate() {
+            let id = states[old].attribute("id").unwrap_or("");
+            if ids.insert(id, new as i32).is_some() {
+                return Err(InvalidState(id.to_string()));
+            }
+            result
+                .accepting
+                .push(states[old].children().any(|n| n.has_tag_name("final")));
+        }
+        for transition in automaton.children().filter(|n| n.has_tag_name("transition")) {
+            let state = |name: &str| {
+                let id = child_text(transition, name).unwrap_or("").trim();
+                ids.get(id).copied().ok_or(InvalidState(id.to_string()))
+            };
+            let (from, to) = (state("from")?, state("to")?);
+            let from_id = child_text(transition, "from").unwrap_or("").trim().to_string();
+            let read = child_text(transition, "read").unwrap_or("");
+            let mut chars = read.chars();
+            let char = match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                (None, _) => return Err(EmptyTransition(from_id)),
+                _ => return Err(MultiCharacterSymbol(read.to_string())),
+            };
+            if !result.alphabet.contains(&char) {
+                result.alphabet.push(char);
+            }
+            if result.transition.insert((from, char), to).is_some_and(|t| t != to) {
+                return Err(Nondeterministic((from_id, char)));
+            }
+        }
+        Ok(result.complete())
+    }
+
+    // states are laid out on a grid, since JFLAP requires coordinates
+    pub fn to_jff(&self) -> String {
+        let mut s = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+        s.push_str("<structure>\n\t<type>fa</type>\n\t<automaton>\n");
+        for i in 0..self.states {
+            let (x, y) = (100 + 150 * (i % 6), 100 + 150 * (i / 6));
+            s.push_str(&format!("\t\t<state id=\"{i}\" name=\"q{i}\">\n"));
+            s.push_str(&format!("\t\t\t<x>{x}.0</x>\n\t\t\t<y>{y}.0</y>\n"));
+            if i == self.start {
+                s.push_str("\t\t\t<initial/>\n");
+            }
+            if self.accepting[i as usize] {
+                s.push_str("\t\t\t<final/>\n");
+            }
+            s.push_str("\t\t</state>\n");
+        }
+        for from in 0..self.states {
+            for &char in &self.alphabet {
+                if let Some(to) = self.transition.get(&(from, char)) {
+                    s.push_str(&format!(
+                        "\t\t<transition>\n\t\t\t<from>{from}</from>\n\t\t\t<to>{to}</to>\n\t\t\t<read>{}</read>\n\t\t</transition>\n",
+                        escape(&char.to_string())
+                    ));
+                }
+            }
+        }
+        s.push_str("\t</automaton>\n</structure>\n");
+        s
+    }
+}
+
+impl Display for JffError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use JffError::*;
+        match self {
+            Xml(e) => write!(f, "invalid xml: {e}"),
+            NotFiniteAutomaton(kind) => write!(f, "expected a finite automaton (type fa), found type {kind}"),
+            NoInitialState => write!(f, "no initial state"),
+            MultipleInitialStates => write!(f, "more than one initial state"),
+            InvalidState(id) => write!(f, "missing, duplicate or unknown state id {id}"),
+            EmptyTransition(id) => write!(f, "nondeterministic: empty (lambda) transition from state {id}"),
+            MultiCharacterSymbol(s) => write!(f, "transition reads more than one character: {s}"),
+            Nondeterministic((id, c)) => {
+                write!(f, "nondeterministic: two transitions for character {c} from state {id}")
+            }
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::{collections::HashMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dfa::DFA;
+
+#[derive(Debug)]
+pub enum DFAJsonError {
+    Syntax(String),
+    InvalidSymbol(String),
+    DuplicateSymbol(char),
+    InvalidStateId(i32),
+    DuplicateState(String),
+    UnknownState(String),
+    UnknownSymbol(char),
+    DuplicateTransition((i32, char)),
+    MissingTransition((i32, char)),
+}
+
+// states can be referred to by id or, if they have one, by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum StateRef {
+    Id(i32),
+    Name(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonState {
+    id: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonTransition {
+    from: StateRef,
+    symbol: String,
+    to: StateRef,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonDFA {
+    alphabet: Vec<String>,
+    states: Vec<JsonState>,
+    start: StateRef,
+    accepting: Vec<StateRef>,
+    transitions: Vec<JsonTransition>,
+    // missing transitions reject instead of making the file invalid
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    partial: bool,
+}
+
+impl DFA {
+    pub fn to_json(&self) -> String {
+        let mut transitions = vec![];
+        for from in 0..self.states {
+            for &char in &self.alphabet {
+                if let Some(&to) = self.transition.get(&(from, char)) {
+                    transitions.push(JsonTransition {
+                        from: StateRef::Id(from),
+                        symbol: char.to_string(),
+                        to: StateRef::Id(to),
+                    });
+                }
+            }
+        }
+        let json = JsonDFA {
+            alphabet: self.alphabet.iter().map(|c| c.to_string()).collect(),
+            states: (0..self.states)
+                .map(|id| JsonState {
+                    id,
+                    name: self.names.get(&id).cloned(),
+                })
+                .collect(),
+            start: StateRef::Id(self.start),
+            accepting: (0..self.states)
+                .filter(|&i| self.accepting[i as usize])
+                .map(StateRef::Id)
+                .collect(),
+            transitions,
+            partial: self.partial,
+        };
+        serde_json::to_string_pretty(&json).unwrap() + "\n"
+    }
+
+    // every state needs exactly one transition for every symbol, unless the dfa is partial
+    pub fn from_json(s: &str) -> Result<Self, DFAJsonError> {
+        use DFAJsonError::*;
+        let json: JsonDFA = serde_json::from_str(s).map_err(|e| Syntax(e.to_string()))?;
+        let mut result = DFA::new();
+        for symbol in &json.alphabet {
+            let mut chars = symbol.chars();
+            let char = match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => return Err(InvalidSymbol(symbol.clone())),
+            };
+            if result.alphabet.contains(&char) {
+                return Err(DuplicateSymbol(char));
+            }
+            result.alphabet.push(char);
+        }
+        result.states = json.states.len() as i32;
+        let mut seen = vec![false; json.states.len()];
+        let mut names: HashMap<&str, i32> = HashMap::new();
+        for state in &json.states {
+            if state.id < 0 || state.id >= result.states {
+                return Err(InvalidStateId(state.id));
+            }
+            if seen[state.id as usize] {
+                return Err(DuplicateState(state.id.to_string()));
+            }
+            seen[state.id as usize] = true;
+            if let Some(name) = &state.name {
+                if names.insert(name, state.id).is_some() {
+                    return Err(DuplicateState(name.clone()));
+                }
+                result.names.insert(state.id, name.clone());
+            }
+        }
+        let resolve = |state: &StateRef| match state {
+            StateRef::Id(id) if *id >= 0 && *id < result.states => Ok(*id),
+            StateRef::Id(id) => Err(UnknownState(id.to_string())),
+            StateRef::Name(name) => names.get(name.as_str()).copied().ok_or(UnknownState(name.clone())),
+        };
+        result.start = resolve(&json.start)?;
+        result.accepting = vec![false; result.states as usize];
+        for state in &json.accepting {
+            result.accepting[resolve(state)? as usize] = true;
+        }
+        for transition in &json.transitions {
+            let from = resolve(&transition.from)?;
+            let to = resolve(&transition.to)?;
+            let mut chars = transition.symbol.chars();
+            let char = match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => return Err(InvalidSymbol(transition.symbol.clone())),
+            };
+            if !result.alphabet.contains(&char) {
+                return Err(UnknownSymbol(char));
+            }
+            if result.transition.insert((from, char), to).is_some() {
+                return Err(DuplicateTransition((from, char)));
+            }
+        }
+        result.partial = json.partial;
+        for from in (0..result.states).filter(|_| !result.partial) {
+            for &char in &result.alphabet {
+                if !result.transition.contains_key(&(from, char)) {
+                    return Err(MissingTransition((from, char)));
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Display for DFAJsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use DFAJsonError::*;
+        match self {
+            Syntax(e) => write!(f, "invalid json: {e}"),
+            InvalidSymbol(s) => write!(f, "symbol \"{s}\" is not a single character"),
+            DuplicateSymbol(c) => write!(f, "symbol {c} appears twice in the alphabet"),
+            InvalidStateId(i) => write!(f, "state id {i} is out of range"),
+            DuplicateState(s) => write!(f, "state {s} is defined twice"),
+            UnknownState(s) => write!(f, "unknown state {s}"),
+            UnknownSymbol(c) => write!(f, "symbol {c} is not in the alphabet"),
+            DuplicateTransition((i, c)) => write!(f, "two transitions for character {c} and state {i}"),
+            MissingTransition((i, c)) => write!(f, "no transition found for character {c} and state {i}"),
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::dfa::DFA;
+
+const RADIUS: f64 = 22.0;
+const COLUMN_WIDTH: f64 = 150.0;
+const ROW_HEIGHT: f64 = 110.0;
+const MARGIN: f64 = 80.0;
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// the point at distance `RADIUS` from `center` in the direction of `toward`
+fn boundary(center: (f64, f64), toward: (f64, f64)) -> (f64, f64) {
+    let (dx, dy) = (toward.0 - center.0, toward.1 - center.1);
+    let length = (dx * dx + dy * dy).sqrt().max(f64::EPSILON);
+    (center.0 + dx / length * RADIUS, center.1 + dy / length * RADIUS)
+}
+
+impl DFA {
+    // the same breadth-first columns as `to_tikz`, in pixels
+    fn svg_positions(&self) -> (Vec<(f64, f64)>, f64, f64) {
+        let mut positions = vec![(0.0, 0.0); self.states as usize];
+        let layers = self.bfs_layers();
+        let mut rows = 1;
+        for (x, layer) in layers.iter().enumerate() {
+            rows = rows.max(layer.len());
+            for (y, &state) in layer.iter().enumerate() {
+                positions[state as usize] = (MARGIN + x as f64 * COLUMN_WIDTH, MARGIN + y as f64 * ROW_HEIGHT);
+            }
+        }
+        let width = 2.0 * MARGIN + (layers.len().max(1) - 1) as f64 * COLUMN_WIDTH;
+        let height = 2.0 * MARGIN + (rows - 1) as f64 * ROW_HEIGHT;
+        (positions, width, height)
+    }
+
+    // a standalone SVG drawing of the state diagram. edges between the same pair of states
+    // in both directions, and edges that skip over a column or stay in one, are drawn as
+    // curves so that they do not overlap each other or other states
+    pub fn to_svg(&self) -> String {
+        let (positions, width, height) = self.svg_positions();
+        let mut s = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"14\">\n"
+        );
+        s.push_str("  <defs>\n    <marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto-start-reverse\">\n      <path d=\"M 0 0 L 10 5 L 0 10 z\"/>\n    </marker>\n  </defs>\n");
+        if self.states > 0 {
+            let (x, y) = positions[self.start as usize];
+            s.push_str(&format!(
+                "  <line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"black\" marker-end=\"url(#arrow)\"/>\n",
+                x - RADIUS - 40.0,
+                x - RADIUS
+            ));
+        }
+        let edges = self.merged_edges(false);
+        for (from, to, chars) in &edges {
+            let label: Vec<String> = chars.iter().map(|c| c.to_string()).collect();
+            let label = escape(&label.join(","));
+            let (x1, y1) = positions[*from as usize];
+            let (x2, y2) = positions[*to as usize];
+            if from == to {
+                let top = y1 - RADIUS;
+                s.push_str(&format!(
+                    "  <path d=\"M {} {} C {} {} {} {} {} {}\" fill=\"none\" stroke=\"black\" marker-end=\"url(#arrow)\"/>\n",
+                    x1 - 10.0,
+                    top + 2.0,
+                    x1 - 30.0,
+                    top - 45.0,
+                    x1 + 30.0,
+                    top - 45.0,
+                    x1 + 10.0,
+                    top + 2.0
+                ));
+                s.push_str(&format!(
+                    "  <text x=\"{x1}\" y=\"{}\" text-anchor=\"middle\">{label}</text>\n",
+                    top - 38.0
+                ));
+                continue;
+            }
+            let reverse = edges.iter().any(|(f, t, _)| f == to && t == from);
+            let adjacent = (x2 - x1 - COLUMN_WIDTH).abs() < 1.0 || (x1 - x2 - COLUMN_WIDTH).abs() < 1.0;
+            let bend = if reverse {
+                30.0
+            } else if !adjacent {
+                50.0
+            } else {
+                0.0
+            };
+            // control point, offset to the left of the direction of travel
+            let (dx, dy) = (x2 - x1, y2 - y1);
+            let length = (dx * dx + dy * dy).sqrt();
+            let control = (
+                (x1 + x2) / 2.0 + dy / length * bend,
+                (y1 + y2) / 2.0 - dx / length * bend,
+            );
+            let start = boundary((x1, y1), control);
+            let end = boundary((x2, y2), control);
+            s.push_str(&format!(
+                "  <path d=\"M {} {} Q {} {} {} {}\" fill=\"none\" stroke=\"black\" marker-end=\"url(#arrow)\"/>\n",
+                start.0, start.1, control.0, control.1, end.0, end.1
+            ));
+            let middle = (
+                0.25 * start.0 + 0.5 * control.0 + 0.25 * end.0 + dy / length * 10.0,
+                0.25 * start.1 + 0.5 * control.1 + 0.25 * end.1 - dx / length * 10.0,
+            );
+            s.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{label}</text>\n",
+                middle.0,
+                middle.1 + 5.0
+            ));
+        }
+        for state in 0..self.states {
+            let (x, y) = positions[state as usize];
+            s.push_str(&format!(
+                "  <circle cx=\"{x}\" cy=\"{y}\" r=\"{RADIUS}\" fill=\"white\" stroke=\"black\"/>\n"
+            ));
+            if self.accepting[state as usize] {
+                s.push_str(&format!(
+                    "  <circle cx=\"{x}\" cy=\"{y}\" r=\"{}\" fill=\"none\" stroke=\"black\"/>\n",
+                    RADIUS - 4.0
+                ));
+            }
+            s.push_str(&format!(
+                "  <text x=\"{x}\" y=\"{}\" text-anchor=\"middle\">{state}</text>\n",
+                y + 5.0
+            ));
+        }
+        s.push_str("</svg>\n");
+        s
+    }
+
+    pub fn info(&self) -> Vec<(&'static str, String)> {
+        let accepting = self.accepting.iter().filter(|&&a| a).count();
+        let dead = (0..self.states).filter(|&i| self.is_permanently_rejecting(i)).count();
+        vec![
+            ("states", self.states.to_string()),
+            ("accepting states", accepting.to_string()),
+            ("alphabet size", self.alphabet.len().to_string()),
+            (
+                "reachable states",
+                self.states_reachable_from(self.start).len().to_string(),
+            ),
+            ("dead states", dead.to_string()),
+            ("states after minimization", self.optimize().states.to_string()),
+            ("accepts nothing", self.is_empty().to_string()),
+        ]
+    }
+
+    // an HTML page with the diagram next to the transition table and `info`
+    pub fn to_html_report(&self) -> String {
+        let mut s = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>dfa</title>\n");
+        s.push_str("<style>\nbody { font-family: sans-serif; display: flex; gap: 2em; align-items: flex-start; }\n");
+        s.push_str("table { border-collapse: collapse; font-family: monospace; }\n");
+        s.push_str("td, th { border: 1px solid #999; padding: 0.2em 0.6em; text-align: center; }\n</style>\n");
+        s.push_str("</head>\n<body>\n<div>\n");
+        s.push_str(&self.to_svg());
+        s.push_str("</div>\n<div>\n<table>\n<tr><th>state</th>");
+        for char in &self.alphabet {
+            s.push_str(&format!("<th>{}</th>", escape(&char.to_string())));
+        }
+        s.push_str("</tr>\n");
+        for state in 0..self.states {
+            let mut name = state.to_string();
+            if state == self.start {
+                name = format!("&rarr;{name}");
+            }
+            if self.accepting[state as usize] {
+                name = format!("{name}*");
+            }
+            s.push_str(&format!("<tr><th>{name}</th>"));
+            for char in &self.alphabet {
+                match self.transition.get(&(state, *char)) {
+                    Some(to) => s.push_str(&format!("<td>{to}</td>")),
+                    None => s.push_str("<td></td>"),
+                }
+            }
+            s.push_str("</tr>\n");
+        }
+        s.push_str("</table>\n<p>&rarr; marks the start state and * the accepting states</p>\n<table>\n");
+        for (name, value) in self.info() {
+            s.push_str(&format!("<tr><th>{name}</th><td>{value}</td></tr>\n"));
+        }
+        s.push_str("</table>\n</div>\n</body>\n</html>\n");
+        s
+    }
+}
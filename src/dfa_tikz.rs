@@ -0,0 +1,73 @@
+use crate::dfa::DFA;
+
+fn escape(s: &str) -> String {
+    let mut result = String::new();
+    for char in s.chars() {
+        match char {
+            '\\' => result.push_str("\\textbackslash{}"),
+            '^' => result.push_str("\\textasciicircum{}"),
+            '~' => result.push_str("\\textasciitilde{}"),
+            '#' | '$' | '%' | '&' | '_' | '{' | '}' => {
+                result.push('\\');
+                result.push(char);
+            }
+            _ => result.push(char),
+        }
+    }
+    result
+}
+
+impl DFA {
+    // a tikzpicture for the automata library. it needs \usepackage{tikz} and
+    // \usetikzlibrary{automata, arrows} in the preamble
+    pub fn to_tikz(&self) -> String {
+        let mut s = String::from("\\begin{tikzpicture}[->, >=stealth, shorten >=1pt, auto, semithick]\n");
+        for (x, layer) in self.bfs_layers().iter().enumerate() {
+            for (y, &state) in layer.iter().enumerate() {
+                let mut style = String::from("state");
+                if state == self.start {
+                    style.push_str(", initial");
+                }
+                if self.accepting[state as usize] {
+                    style.push_str(", accepting");
+                }
+                s.push_str(&format!(
+                    "  \\node[{style}] (q{state}) at ({}, {}) {{$q_{{{state}}}$}};\n",
+                    x * 3,
+                    -2 * y as i32
+                ));
+            }
+        }
+        let edges = self.merged_edges(false);
+        if !edges.is_empty() {
+            s.push_str("  \\path\n");
+            for (from, to, chars) in &edges {
+                let label: Vec<String> = chars.iter().map(|c| escape(&c.to_string())).collect();
+                let style = if from == to {
+                    "[loop above] "
+                } else if edges.iter().any(|(f, t, _)| f == to && t == from) {
+                    "[bend left] "
+                } else {
+                    ""
+                };
+                s.push_str(&format!(
+                    "    (q{from}) edge {style}node {{\\texttt{{{}}}}} (q{to})\n",
+                    label.join(",")
+                ));
+            }
+            s.push_str("  ;\n");
+        }
+        s.push_str("\\end{tikzpicture}\n");
+        s
+    }
+
+    // a complete LaTeX document around `to_tikz`, for the standalone class
+    pub fn to_tikz_document(&self) -> String {
+        let mut s = String::from("\\documentclass[tikz, border=5pt]{standalone}\n");
+        s.push_str("\\usetikzlibrary{automata, arrows}\n");
+        s.push_str("\\begin{document}\n");
+        s.push_str(&self.to_tikz());
+        s.push_str("\\end{document}\n");
+        s
+    }
+}
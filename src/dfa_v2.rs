@@ -0,0 +1,266 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::dfa::DFA;
+
+pub const HEADER: &str = "dfa v2";
+
+#[derive(Debug)]
+pub enum DFAParseError {
+    MissingHeader,
+    InvalidLine(usize),
+    InvalidSymbol(usize),
+    UnknownSymbol((usize, char)),
+    Nondeterministic((usize, char)),
+    NoStates,
+    InvalidAlphabetEntry((usize, String)),
+}
+
+// whether the first non-empty line is the v2 header
+pub fn is_v2(s: &str) -> bool {
+    s.lines()
+        .find(|l| !l.trim().is_empty())
+        .is_some_and(|l| l.trim() == HEADER)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(char),
+    Dash,
+    Arrow,
+    Comma,
+}
+
+// splits a line into tokens, dropping a comment that starts with # outside of quotes.
+// quoted symbols are written 'c' with the escapes \' \\ \n \r \t and \u{hex}
+fn tokenize(line: &str) -> Option<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = line.chars().peekable();
+    while let Some(&char) = chars.peek() {
+        match char {
+            '#' => break,
+            _ if char.is_whitespace() => {
+                chars.next();
+            }
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma);
+            }
+            '-' => {
+                chars.next();
+                if chars.peek() == Some(&'>') {
+                    chars.next();
+                    tokens.push(Token::Arrow);
+                } else {
+                    tokens.push(Token::Dash);
+                }
+            }
+            '\'' => {
+                chars.next();
+                let symbol = match chars.next()? {
+                    '\\' => match chars.next()? {
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => {
+                            if chars.next()? != '{' {
+                                return None;
+                            }
+                            let hex: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                            char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                        }
+                        c @ ('\\' | '\'') => c,
+                        _ => return None,
+                    },
+                    '\'' => return None,
+                    c => c,
+                };
+                if chars.next()? != '\'' {
+                    return None;
+                }
+                tokens.push(Token::Quoted(symbol));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '#' | ',' | '-' | '\'') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Some(tokens)
+}
+
+fn symbol(token: &Token) -> Option<char> {
+    match token {
+        Token::Quoted(c) => Some(*c),
+        Token::Word(w) if w.chars().count() == 1 => w.chars().next(),
+        _ => None,
+    }
+}
+
+// symbols, separated by commas or whitespace
+fn symbols(tokens: &[Token]) -> Option<Vec<char>> {
+    tokens.iter().filter(|t| **t != Token::Comma).map(symbol).collect()
+}
+
+fn names(tokens: &[Token]) -> Option<Vec<&str>> {
+    tokens
+        .iter()
+        .filter(|t| **t != Token::Comma)
+        .map(|t| match t {
+            Token::Word(w) => Some(w.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn quote(char: char) -> String {
+    match char {
+        '\n' => String::from("'\\n'"),
+        '\r' => String::from("'\\r'"),
+        '\t' => String::from("'\\t'"),
+        '\\' | '\'' => format!("'\\{char}'"),
+        _ if char.is_control() => format!("'\\u{{{:x}}}'", char as u32),
+        _ if char.is_whitespace() || matches!(char, '#' | ',' | '-' | '>') => format!("'{char}'"),
+        _ => char.to_string(),
+    }
+}
+
+impl DFA {
+    // the first line is the header `dfa v2`, and every other line is one of
+    //   alphabet <symbols>
+    //   states <names>
+    //   start <name>
+    //   accept <names>
+    //   <name> -<symbols>-> <name>
+    //   partial
+    // states are numbered in order of first mention, and the start state defaults to the first.
+    // missing transitions go to a rejecting sink state that is added at the end, or are kept as
+    // they are if the file says `partial`
+    pub fn from_v2_string(s: &str) -> Result<Self, DFAParseError> {
+        use DFAParseError::*;
+        let mut lines = s.lines().enumerate().skip_while(|(_, l)| l.trim().is_empty());
+        match lines.next() {
+            Some((_, line)) if line.trim() == HEADER => {}
+            _ => return Err(MissingHeader),
+        }
+        let mut result = DFA::new();
+        let mut ids: HashMap<String, i32> = HashMap::new();
+        let mut start = None;
+        let mut partial = false;
+        let mut accepting = vec![];
+        let mut edges = vec![];
+        let mut id = |name: &str, result: &mut DFA| {
+            *ids.entry(name.to_string()).or_insert_with(|| {
+                result.states += 1;
+                result.states - 1
+            })
+        };
+        for (i, line) in lines {
+            let line_number = i + 1;
+            let tokens = tokenize(line).ok_or(InvalidLine(line_number))?;
+            match &tokens[..] {
+                [] => {}
+                [Token::Word(w), rest @ ..] if w == "alphabet" => {
+                    for char in symbols(rest).ok_or(InvalidSymbol(line_number))? {
+                        if !result.alphabet.contains(&char) {
+                            result.alphabet.push(char);
+                        }
+                    }
+                }
+                [Token::Word(w), rest @ ..] if w == "states" => {
+                    for name in names(rest).ok_or(InvalidLine(line_number))? {
+                        id(name, &mut result);
+                    }
+                }
+                [Token::Word(w), Token::Word(name)] if w == "start" => start = Some(id(name, &mut result)),
+                [Token::Word(w)] if w == "partial" => partial = true,
+                [Token::Word(w), rest @ ..] if w == "accept" || w == "accepting" => {
+                    for name in names(rest).ok_or(InvalidLine(line_number))? {
+                        accepting.push(id(name, &mut result));
+                    }
+                }
+                [Token::Word(from), Token::Dash, middle @ .., Token::Arrow, Token::Word(to)] => {
+                    let chars = symbols(middle).ok_or(InvalidSymbol(line_number))?;
+                    let (from, to) = (id(from, &mut result), id(to, &mut result));
+                    edges.extend(chars.into_iter().map(|c| (line_number, from, c, to)));
+                }
+                _ => return Err(InvalidLine(line_number)),
+            }
+        }
+        if result.states == 0 {
+            return Err(NoStates);
+        }
+        result.start = start.unwrap_or(0);
+        result.accepting = vec![false; result.states as usize];
+        for state in accepting {
+            result.accepting[state as usize] = true;
+        }
+        for (line_number, from, char, to) in edges {
+            if !result.alphabet.contains(&char) {
+                return Err(UnknownSymbol((line_number, char)));
+            }
+            if result.transition.insert((from, char), to).is_some_and(|t| t != to) {
+                return Err(Nondeterministic((line_number, char)));
+            }
+        }
+        if partial {
+            result.partial = true;
+            return Ok(result);
+        }
+        Ok(result.complete())
+    }
+
+    // states are named q0, q1, ... and dead states other than the start state are left out,
+    // so their transitions become the implicit sink when the file is read back
+    pub fn to_v2_string(&self) -> String {
+        let hidden = self.hidden_states(true);
+        let mut s = format!("{HEADER}\n");
+        if self.partial {
+            s.push_str("partial\n");
+        }
+        let alphabet: Vec<String> = self.alphabet.iter().map(|&c| quote(c)).collect();
+        s.push_str(&format!("alphabet {}\n", alphabet.join(" ")));
+        let visible: Vec<String> = (0..self.states)
+            .filter(|&i| !hidden[i as usize])
+            .map(|i| format!("q{i}"))
+            .collect();
+        s.push_str(&format!("states {}\n", visible.join(" ")));
+        if self.states > 0 {
+            s.push_str(&format!("start q{}\n", self.start));
+        }
+        let accepting: Vec<String> = (0..self.states)
+            .filter(|&i| self.accepting[i as usize])
+            .map(|i| format!("q{i}"))
+            .collect();
+        if !accepting.is_empty() {
+            s.push_str(&format!("accept {}\n", accepting.join(" ")));
+        }
+        for (from, to, chars) in self.merged_edges(true) {
+            let chars: Vec<String> = chars.iter().map(|&c| quote(c)).collect();
+            s.push_str(&format!("q{from} -{}-> q{to}\n", chars.join(",")));
+        }
+        s
+    }
+}
+
+impl Display for DFAParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use DFAParseError::*;
+        match self {
+            MissingHeader => write!(f, "the first line must be \"{HEADER}\""),
+            InvalidLine(i) => write!(f, "invalid line {i}"),
+            InvalidSymbol(i) => write!(f, "invalid symbol on line {i}"),
+            UnknownSymbol((i, c)) => write!(f, "symbol {c} on line {i} is not in the alphabet"),
+            Nondeterministic((i, c)) => write!(f, "second transition for symbol {c} on line {i}"),
+            NoStates => write!(f, "no states"),
+            InvalidAlphabetEntry((i, s)) => write!(f, "invalid alphabet entry {s} on line {i}"),
+        }
+    }
+}
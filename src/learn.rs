@@ -0,0 +1,506 @@
+use std::{
+    collections::HashMap,
+    io::{ErrorKind, Write},
+    process::{Command, Stdio},
+};
+
+use crate::dfa::DFA;
+
+pub trait MembershipOracle {
+    fn query(&mut self, string: &str) -> bool;
+}
+
+pub trait EquivalenceOracle {
+    fn find_counterexample(&mut self, hypothesis: &DFA, membership: &mut dyn MembershipOracle) -> Option<String>;
+}
+
+impl<F: FnMut(&str) -> bool> MembershipOracle for F {
+    fn query(&mut self, string: &str) -> bool {
+        self(string)
+    }
+}
+
+impl MembershipOracle for DFA {
+    fn query(&mut self, string: &str) -> bool {
+        self.evaluate(string).unwrap_or(false)
+    }
+}
+
+// runs a program once per query with the string on its standard input;
+// the string is accepted if the program exits successfully. the trait has no way to
+// fail, so the first error running the program is kept in `error` and every later
+// query is answered with false without running it again
+pub struct CommandOracle {
+    pub command: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl CommandOracle {
+    pub fn new(command: Vec<String>) -> Self {
+        CommandOracle { command, error: None }
+    }
+
+    fn run(&self, string: &str) -> Result<bool, String> {
+        let name = self.command.first().ok_or("no command given")?;
+        let mut child = Command::new(name)
+            .args(&self.command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("unable to run {name}: {e}"))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // a program may decide without reading all of its input
+            match stdin.write_all(string.as_bytes()) {
+                Err(e) if e.kind() != ErrorKind::BrokenPipe => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!("unable to write to {name}: {e}"));
+                }
+                _ => {}
+            }
+        }
+        let status = child.wait().map_err(|e| format!("unable to run {name}: {e}"))?;
+        Ok(status.success())
+    }
+}
+
+impl MembershipOracle for CommandOracle {
+    fn query(&mut self, string: &str) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        match self.run(string) {
+            Ok(a) => a,
+            Err(e) => {
+                self.error = Some(e);
+                false
+            }
+        }
+    }
+}
+
+pub struct RandomSampling {
+    pub samples: usize,
+    pub max_length: usize,
+    pub seed: u64,
+}
+
+impl EquivalenceOracle for RandomSampling {
+    fn find_counterexample(&mut self, hypothesis: &DFA, membership: &mut dyn MembershipOracle) -> Option<String> {
+        if hypothesis.alphabet.is_empty() {
+            return None;
+        }
+        for _ in 0..self.samples {
+            let length = (self.next() % (self.max_length as u64 + 1)) as usize;
+            let string: String = (0..length)
+                .map(|_| hypothesis.alphabet[(self.next() % hypothesis.alphabet.len() as u64) as usize])
+                .collect();
+            if hypothesis.evaluate(&string).unwrap_or(false) != membership.query(&string) {
+                return Some(string);
+            }
+        }
+        None
+    }
+}
+
+impl RandomSampling {
+    // xorshift, good enough for picking test strings and keeps runs reproducible
+    fn next(&mut self) -> u64 {
+        if self.seed == 0 {
+            self.seed = 0x9E37_79B9_7F4A_7C15;
+        }
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+}
+
+// Chow's W-method: finds every counterexample, provided the target has at most
+// `extra_states` more states than the hypothesis
+pub struct WMethod {
+    pub extra_states: usize,
+}
+
+impl EquivalenceOracle for WMethod {
+    fn find_counterexample(&mut self, hypothesis: &DFA, membership: &mut dyn MembershipOracle) -> Option<String> {
+        let access = access_strings(hypothesis);
+        let mut characterizing = characterizing_set(hypothesis);
+        if characterizing.is_empty() {
+            characterizing.push(String::new());
+        }
+        let mut middles = vec![String::new()];
+        let mut layer = vec![String::new()];
+        for _ in 0..=self.extra_states {
+            layer = layer
+                .iter()
+                .flat_map(|s| hypothesis.alphabet.iter().map(move |c| format!("{s}{c}")))
+                .collect();
+            middles.extend(layer.clone());
+        }
+        for prefix in access.iter().flatten() {
+            for middle in &middles {
+                for suffix in &characterizing {
+                    let string = format!("{prefix}{middle}{suffix}");
+                    if hypothesis.evaluate(&string).unwrap_or(false) != membership.query(&string) {
+                        return Some(string);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<A: EquivalenceOracle, B: EquivalenceOracle> EquivalenceOracle for (A, B) {
+    fn find_counterexample(&mut self, hypothesis: &DFA, membership: &mut dyn MembershipOracle) -> Option<String> {
+        self.0
+            .find_counterexample(hypothesis, membership)
+            .or_else(|| self.1.find_counterexample(hypothesis, membership))
+    }
+}
+
+// a shortest string leading to each state, or None for unreachable states
+fn access_strings(dfa: &DFA) -> Vec<Option<String>> {
+    let mut result = vec![None; dfa.states.max(0) as usize];
+    if dfa.states == 0 {
+        return result;
+    }
+    result[dfa.start as usize] = Some(String::new());
+    let mut queue = vec![dfa.start];
+    let mut next = 0;
+    while next < queue.len() {
+        let state = queue[next];
+        for &char in &dfa.alphabet {
+            if let Some(&target) = dfa.transition.get(&(state, char)) {
+                if result[target as usize].is_none() {
+                    result[target as usize] = Some(format!("{}{char}", result[state as usize].as_ref().unwrap()));
+                    queue.push(target);
+                }
+            }
+        }
+        next += 1;
+    }
+    result
+}
+
+// a distinguishing suffix for every pair of distinguishable states
+fn characterizing_set(dfa: &DFA) -> Vec<String> {
+    let n = dfa.states.max(0) as usize;
+    let mut suffix: HashMap<(i32, i32), String> = HashMap::new();
+    for i in 0..n {
+        for j in 0..i {
+            if dfa.accepting[i] != dfa.accepting[j] {
+                suffix.insert((i as i32, j as i32), String::new());
+            }
+        }
+    }
+    loop {
+        let mut changed = false;
+        for i in 0..n as i32 {
+            for j in 0..i {
+                if suffix.contains_key(&(i, j)) {
+                    continue;
+                }
+                for &char in &dfa.alphabet {
+                    let (a, b) = match (dfa.transition.get(&(i, char)), dfa.transition.get(&(j, char))) {
+                        (Some(&a), Some(&b)) if a != b => (a.max(b), a.min(b)),
+                        _ => continue,
+                    };
+                    if let Some(rest) = suffix.get(&(a, b)) {
+                        suffix.insert((i, j), format!("{char}{rest}"));
+                        changed = true;
+                        break;
+                    }
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    let mut result: Vec<String> = suffix.into_values().collect();
+    result.sort();
+    result.dedup();
+    result
+}
+
+struct ObservationTable<'a> {
+    alphabet: Vec<char>,
+    membership: &'a mut dyn MembershipOracle,
+    cache: HashMap<String, bool>,
+    prefixes: Vec<String>,
+    suffixes: Vec<String>,
+}
+
+// every membership query, including those of the equivalence oracle, goes through the cache
+impl MembershipOracle for ObservationTable<'_> {
+    fn query(&mut self, string: &str) -> bool {
+        if let Some(&a) = self.cache.get(string) {
+            return a;
+        }
+        let result = self.membership.query(string);
+        self.cache.insert(string.to_string(), result);
+        result
+    }
+}
+
+impl ObservationTable<'_> {
+    fn row(&mut self, prefix: &str) -> Vec<bool> {
+        let suffixes = self.suffixes.clone();
+        suffixes.iter().map(|s| self.query(&format!("{prefix}{s}"))).collect()
+    }
+
+    // adds one-letter extensions with new rows to the prefixes until every extension's row
+    // already belongs to a prefix; prefixes keep pairwise different rows throughout
+    fn close(&mut self) -> Vec<Vec<bool>> {
+        let mut rows: Vec<Vec<bool>> = self.prefixes.clone().iter().map(|p| self.row(p)).collect();
+        let mut next = 0;
+        while next < self.prefixes.len() {
+            for char in self.alphabet.clone() {
+                let extension = format!("{}{char}", self.prefixes[next]);
+                let row = self.row(&extension);
+                if !rows.contains(&row) {
+                    self.prefixes.push(extension);
+                    rows.push(row);
+                }
+            }
+            next += 1;
+        }
+        rows
+    }
+
+    fn hypothesis(&mut self) -> DFA {
+        let rows = self.close();
+        let mut result = DFA::new();
+        result.alphabet = self.alphabet.clone();
+        result.states = self.prefixes.len() as i32;
+        for (i, prefix) in self.prefixes.clone().iter().enumerate() {
+            result.accepting.push(self.query(prefix));
+            for char in self.alphabet.clone() {
+                let row = self.row(&format!("{prefix}{char}"));
+                let target = rows.iter().position(|r| *r == row).unwrap();
+                result.transition.insert((i as i32, char), target as i32);
+            }
+        }
+        result
+    }
+
+    // Rivest-Schapire: replacing the prefix of the counterexample read so far by the access
+    // string of the hypothesis state it leads to flips the answer somewhere between the whole
+    // counterexample and the hypothesis' own answer; the suffix after that point is a new
+    // distinguishing experiment
+    fn add_counterexample(&mut self, hypothesis: &DFA, counterexample: &str) {
+        let chars: Vec<char> = counterexample.chars().collect();
+        let mut states = vec![hypothesis.start];
+        for &char in &chars {
+            let state = *states.last().unwrap();
+            states.push(*hypothesis.transition.get(&(state, char)).unwrap_or(&0));
+        }
+        let alpha = |this: &mut Self, i: usize| {
+            let rest: String = chars[i..].iter().collect();
+            let prefix = this.prefixes[states[i] as usize].clone();
+            this.query(&format!("{prefix}{rest}"))
+        };
+        let (mut low, mut high) = (0, chars.len());
+        let expected = alpha(self, low);
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            if alpha(self, middle) == expected {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        let suffix: String = chars[high.min(chars.len())..].iter().collect();
+        if !self.suffixes.contains(&suffix) {
+            self.suffixes.push(suffix);
+        }
+    }
+}
+
+pub fn lstar(alphabet: &[char], membership: &mut dyn MembershipOracle, equivalence: &mut dyn EquivalenceOracle) -> DFA {
+    let mut table = ObservationTable {
+        alphabet: alphabet.to_vec(),
+        membership,
+        cache: HashMap::new(),
+        prefixes: vec![String::new()],
+        suffixes: vec![String::new()],
+    };
+    loop {
+        let hypothesis = table.hypothesis();
+        match equivalence.find_counterexample(&hypothesis, &mut table) {
+            Some(counterexample) => table.add_counterexample(&hypothesis, &counterexample),
+            None => return hypothesis,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PrefixTree {
+    transition: Vec<HashMap<char, usize>>,
+    label: Vec<Option<bool>>,
+}
+
+impl PrefixTree {
+    // nodes are numbered in length-lexicographic order of their access strings,
+    // so smaller ids are the states red-blue merging should look at first.
+    // a string that is both a positive and a negative sample is returned as the error
+    fn new(positive: &[&str], negative: &[&str]) -> Result<Self, String> {
+        let mut prefixes: Vec<Vec<char>> = vec![];
+        for string in positive.iter().chain(negative) {
+            let chars: Vec<char> = string.chars().collect();
+            for i in 0..=chars.len() {
+                prefixes.push(chars[..i].to_vec());
+            }
+        }
+        prefixes.sort_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+        prefixes.dedup();
+        let index: HashMap<&Vec<char>, usize> = prefixes.iter().enumerate().map(|(i, p)| (p, i)).collect();
+        let mut result = PrefixTree {
+            transition: vec![HashMap::new(); prefixes.len()],
+            label: vec![None; prefixes.len()],
+        };
+        for (i, prefix) in prefixes.iter().enumerate().skip(1) {
+            let parent = index[&prefix[..prefix.len() - 1].to_vec()];
+            result.transition[parent].insert(*prefix.last().unwrap(), i);
+        }
+        for (strings, label) in [(negative, false), (positive, true)] {
+            for string in strings {
+                let chars: Vec<char> = string.chars().collect();
+                let node = index[&chars];
+                if result.label[node] == Some(!label) {
+                    return Err(string.to_string());
+                }
+                result.label[node] = Some(label);
+            }
+        }
+        Ok(result)
+    }
+
+    // points `from` on `char` at `red` and folds the subtree below the blue state into it.
+    // returns None on a label conflict, otherwise how many labelled states agreed
+    fn merge(&self, from: usize, char: char, red: usize, blue: usize) -> Option<(Self, usize)> {
+        let mut result = self.clone();
+        result.transition[from].insert(char, red);
+        let mut score = 0;
+        let mut pairs = vec![(red, blue)];
+        while let Some((red, blue)) = pairs.pop() {
+            match (result.label[red], result.label[blue]) {
+                (Some(a), Some(b)) if a != b => return None,
+                (Some(_), Some(_)) => score += 1,
+                (None, b) => result.label[red] = b,
+                _ => {}
+            }
+            for (char, child) in result.transition[blue].clone() {
+                match result.transition[red].get(&char) {
+                    Some(&existing) => pairs.push((existing, child)),
+                    None => {
+                        result.transition[red].insert(char, child);
+                    }
+                }
+            }
+        }
+        Some((result, score))
+    }
+
+    fn blue_states(&self, red: &[usize]) -> Vec<(usize, char, usize)> {
+        let mut result = vec![];
+        for &r in red {
+            for (&char, &target) in &self.transition[r] {
+                if !red.contains(&target) && !result.iter().any(|&(_, _, b)| b == target) {
+                    result.push((r, char, target));
+                }
+            }
+        }
+        result.sort_by_key(|&(_, _, b)| b);
+        result
+    }
+
+    fn to_dfa(&self, red: &[usize], alphabet: &[char]) -> DFA {
+        let mut result = DFA::new();
+        result.alphabet = alphabet.to_vec();
+        result.states = red.len() as i32 + 1;
+        let sink = red.len() as i32;
+        for &r in red {
+            result.accepting.push(self.label[r] == Some(true));
+        }
+        result.accepting.push(false);
+        for (i, &r) in red.iter().enumerate() {
+            for &char in alphabet {
+                let target = match self.transition[r].get(&char) {
+                    Some(t) => red.iter().position(|a| a == t).unwrap() as i32,
+                    None => sink,
+                };
+                result.transition.insert((i as i32, char), target);
+            }
+        }
+        for &char in alphabet {
+            result.transition.insert((sink, char), sink);
+        }
+        result.optimize()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeHeuristic {
+    Rpni,
+    Edsm,
+}
+
+pub fn rpni(positive: &[&str], negative: &[&str], alph: &[char]) -> Result<DFA, String> {
+    passive(positive, negative, alph, MergeHeuristic::Rpni)
+}
+
+pub fn edsm(positive: &[&str], negative: &[&str], alph: &[char]) -> Result<DFA, String> {
+    passive(positive, negative, alph, MergeHeuristic::Edsm)
+}
+
+// red-blue state merging on the prefix tree acceptor. RPNI merges the first blue state
+// into the first red state it is consistent with; EDSM scores every red-blue pair by how many
+// labels the merge confirms and performs the best one. a blue state that cannot be merged
+// anywhere is promoted to red. fails with the first string that is both a positive and a negative sample
+pub fn passive(positive: &[&str], negative: &[&str], alph: &[char], heuristic: MergeHeuristic) -> Result<DFA, String> {
+    let mut tree = PrefixTree::new(positive, negative)?;
+    let mut red = vec![0];
+    loop {
+        let blue = tree.blue_states(&red);
+        if blue.is_empty() {
+            return Ok(tree.to_dfa(&red, alph));
+        }
+        match heuristic {
+            MergeHeuristic::Rpni => {
+                let (from, char, b) = blue[0];
+                match red.iter().find_map(|&r| tree.merge(from, char, r, b)) {
+                    Some((merged, _)) => tree = merged,
+                    None => red.push(b),
+                }
+            }
+            MergeHeuristic::Edsm => {
+                let mut best: Option<(PrefixTree, usize)> = None;
+                let mut promote = None;
+                for &(from, char, b) in &blue {
+                    let mut mergeable = false;
+                    for &r in &red {
+                        if let Some((merged, score)) = tree.merge(from, char, r, b) {
+                            mergeable = true;
+                            if best.as_ref().is_none_or(|(_, s)| score > *s) {
+                                best = Some((merged, score));
+                            }
+                        }
+                    }
+                    if !mergeable {
+                        promote = Some(b);
+                        break;
+                    }
+                }
+                match (promote, best) {
+                    (Some(b), _) => red.push(b),
+                    (None, Some((merged, _))) => tree = merged,
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::{collections::HashMap, fmt::Display, fs::File, io::Read, ops::Range, path::Path};
+
+use crate::dfa::{DFAFileError, DFA};
+
+pub type TokenName = String;
+
+#[derive(Debug, Clone)]
+pub struct Lexer {
+    pub names: Vec<TokenName>,
+    pub dfa: DFA,
+    pub labels: Vec<Option<usize>>,
+    pub dead: Vec<bool>,
+}
+
+#[derive(Debug)]
+pub enum LexError {
+    UnableToOpen(String),
+    InvalidSpecLine(usize),
+    Untokenizable(usize),
+    EmptyRule(TokenName),
+    InvalidDfa((String, DFAFileError)),
+}
+
+impl Lexer {
+    pub fn new(rules: &[(TokenName, DFA)]) -> Result<Self, LexError> {
+        let mut alphabet: Vec<char> = vec![];
+        for (_, dfa) in rules {
+            for &char in &dfa.alphabet {
+                if !alphabet.contains(&char) {
+                    alphabet.push(char);
+                }
+            }
+        }
+        // missing transitions, in partial rules or otherwise, go to a rejecting sink
+        let dfas: Vec<DFA> = rules
+            .iter()
+            .map(|(_, dfa)| {
+                alphabet
+                    .iter()
+                    .fold(dfa.complete(), |dfa, &char| dfa.add_char_accept(char, false))
+            })
+            .collect();
+        let dead: Vec<Vec<bool>> = dfas
+            .iter()
+            .map(|dfa| (0..dfa.states).map(|i| dfa.is_permanently_rejecting(i)).collect())
+            .collect();
+
+        let mut result = Lexer {
+            names: rules.iter().map(|(name, _)| name.clone()).collect(),
+            dfa: DFA::new(),
+            labels: vec![],
+            dead: vec![],
+        };
+        result.dfa.alphabet = alphabet.clone();
+        if let Some((name, _)) = rules.iter().find(|(_, dfa)| dfa.states == 0) {
+            return Err(LexError::EmptyRule(name.clone()));
+        }
+        let mut index: HashMap<Vec<i32>, i32> = HashMap::new();
+        let mut queue: Vec<Vec<i32>> = vec![dfas.iter().map(|dfa| dfa.start).collect()];
+        index.insert(queue[0].clone(), 0);
+        let mut next = 0;
+        while next < queue.len() {
+            let tuple = queue[next].clone();
+            let label = (0..dfas.len()).find(|&i| dfas[i].accepting[tuple[i] as usize]);
+            result.labels.push(label);
+            result.dfa.accepting.push(label.is_some());
+            result.dead.push((0..dfas.len()).all(|i| dead[i][tuple[i] as usize]));
+            for &char in &alphabet {
+                let target: Vec<i32> = (0..dfas.len())
+                    .map(|i| *dfas[i].transition.get(&(tuple[i], char)).unwrap())
+                    .collect();
+                let id = match index.get(&target) {
+                    Some(&id) => id,
+                    None => {
+                        let id = queue.len() as i32;
+                        index.insert(target.clone(), id);
+                        queue.push(target);
+                        id
+                    }
+                };
+                result.dfa.transition.insert((next as i32, char), id);
+            }
+            next += 1;
+        }
+        result.dfa.states = queue.len() as i32;
+        Ok(result)
+    }
+
+    pub fn open_spec_file(path: &str) -> Result<Self, LexError> {
+        let mut contents = String::new();
+        if File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut contents))
+            .is_err()
+        {
+            return Err(LexError::UnableToOpen(path.to_string()));
+        }
+        let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+        let mut rules = vec![];
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.split('%').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (name, file) = match line.split_once(char::is_whitespace) {
+                Some((name, file)) => (name.trim(), file.trim()),
+                None => return Err(LexError::InvalidSpecLine(i + 1)),
+            };
+            let file = dir.join(file);
+            match DFA::open_dfa_file(&file.to_string_lossy()) {
+                Ok(dfa) => rules.push((name.to_string(), dfa)),
+                Err(e) => return Err(LexError::InvalidDfa((file.to_string_lossy().to_string(), e))),
+            }
+        }
+        Lexer::new(&rules)
+    }
+
+    fn longest_token_at(&self, string: &str, start: usize) -> Option<(usize, usize)> {
+        let mut state = 0;
+        let mut result = None;
+        for (i, char) in string[start..].char_indices() {
+            match self.dfa.transition.get(&(state, char)) {
+                Some(&a) if !self.dead[a as usize] => state = a,
+                _ => break,
+            }
+            if let Some(label) = self.labels[state as usize] {
+                result = Some((label, start + i + char.len_utf8()));
+            }
+        }
+        result
+    }
+
+    pub fn tokenize(&self, string: &str) -> Result<Vec<(TokenName, Range<usize>)>, LexError> {
+        let mut result = vec![];
+        let mut position = 0;
+        while position < string.len() {
+            if self.dfa.states == 0 {
+                return Err(LexError::Untokenizable(position));
+            }
+            match self.longest_token_at(string, position) {
+                Some((label, end)) => {
+                    result.push((self.names[label].clone(), position..end));
+                    position = end;
+                }
+                None => return Err(LexError::Untokenizable(position)),
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use LexError::*;
+        match self {
+            UnableToOpen(path) => write!(f, "unable to open file {path}"),
+            InvalidSpecLine(line) => write!(f, "invalid token spec on line {line}"),
+            Untokenizable(position) => write!(f, "untokenizable input at byte {position}"),
+            EmptyRule(name) => write!(f, "the dfa for token {name} has no states"),
+            InvalidDfa((path, e)) => write!(f, "unable to open file {path}: {e}"),
+        }
+    }
+}
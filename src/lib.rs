@@ -1,3 +1,21 @@
+pub mod buchi;
+pub mod cfg;
+pub mod codegen;
 pub mod dfa;
+pub mod dfa_binary;
+pub mod dfa_dot;
 pub mod dfa_gen;
 pub mod dfa_interpreter;
+pub mod dfa_jff;
+pub mod dfa_json;
+pub mod dfa_svg;
+pub mod dfa_tikz;
+pub mod dfa_v2;
+pub mod learn;
+pub mod lexer;
+pub mod mealy;
+pub mod moore;
+pub mod pda;
+pub mod transducer;
+pub mod turing;
+pub mod two_way_dfa;
@@ -0,0 +1,210 @@
+use std::{collections::HashMap, fmt::Display, fs::File, hash::Hash, io::Read};
+
+use crate::{
+    dfa::{DFAEvaluationError, Symbol},
+    moore::{reachable_states, refine_partition, MooreMachine},
+};
+
+#[derive(Debug, Clone)]
+pub struct MealyMachine<O> {
+    pub alphabet: Vec<char>,
+    pub states: i32,
+    pub transition: HashMap<(i32, char), i32>,
+    pub output: HashMap<(i32, char), O>,
+}
+
+impl<O: Clone> MealyMachine<O> {
+    pub fn step(&self, state: i32, char: char) -> Result<(i32, O), DFAEvaluationError> {
+        use DFAEvaluationError::*;
+        if !self.alphabet.contains(&char) {
+            return Err(InvalidChar(char));
+        }
+        if state < 0 || state >= self.states {
+            return Err(InvalidState(state));
+        }
+        match (self.transition.get(&(state, char)), self.output.get(&(state, char))) {
+            (Some(&a), Some(_)) if a < 0 || a >= self.states => Err(InvalidState(a)),
+            (Some(&a), Some(o)) => Ok((a, o.clone())),
+            _ => Err(NoTransition((state, char))),
+        }
+    }
+
+    pub fn run(&self, string: &str) -> Result<Vec<O>, DFAEvaluationError> {
+        let mut state = 0;
+        let mut result = vec![];
+        for char in string.chars() {
+            let (next, output) = self.step(state, char)?;
+            state = next;
+            result.push(output);
+        }
+        Ok(result)
+    }
+}
+
+impl<O: Clone + Eq + Hash> MealyMachine<O> {
+    pub fn to_moore(&self, initial: O) -> MooreMachine<O> {
+        let mut result = MooreMachine {
+            alphabet: self.alphabet.clone(),
+            states: 0,
+            output: vec![],
+            transition: HashMap::new(),
+        };
+        if self.states == 0 {
+            return result;
+        }
+        let mut index: HashMap<(i32, O), i32> = HashMap::new();
+        let mut queue = vec![(0, initial.clone())];
+        index.insert((0, initial), 0);
+        let mut next = 0;
+        while next < queue.len() {
+            let (state, output) = queue[next].clone();
+            result.output.push(output);
+            for &char in &self.alphabet {
+                if let (Some(&target), Some(o)) = (self.transition.get(&(state, char)), self.output.get(&(state, char)))
+                {
+                    let key = (target, o.clone());
+                    let id = match index.get(&key) {
+                        Some(&id) => id,
+                        None => {
+                            let id = queue.len() as i32;
+                            index.insert(key.clone(), id);
+                            queue.push(key);
+                            id
+                        }
+                    };
+                    result.transition.insert((next as i32, char), id);
+                }
+            }
+            next += 1;
+        }
+        result.states = queue.len() as i32;
+        result
+    }
+
+    pub fn minimize(&self) -> Self {
+        if self.states == 0 {
+            return self.clone();
+        }
+        let reachable = reachable_states(self.states, &self.alphabet, &self.transition);
+        let mut ids: HashMap<Vec<Option<&O>>, usize> = HashMap::new();
+        let initial: Vec<usize> = reachable
+            .iter()
+            .map(|&i| {
+                let row = self.alphabet.iter().map(|c| self.output.get(&(i, *c))).collect();
+                let next = ids.len();
+                *ids.entry(row).or_insert(next)
+            })
+            .collect();
+        let (classes, count) = refine_partition(&reachable, initial, &self.alphabet, &self.transition);
+        let mut result = MealyMachine {
+            alphabet: self.alphabet.clone(),
+            states: count as i32,
+            transition: HashMap::new(),
+            output: HashMap::new(),
+        };
+        for &state in &reachable {
+            let class = classes[&state];
+            for &char in &self.alphabet {
+                if let Some(target) = self.transition.get(&(state, char)) {
+                    result.transition.insert((class, char), classes[target]);
+                }
+                if let Some(output) = self.output.get(&(state, char)) {
+                    result.output.insert((class, char), output.clone());
+                }
+            }
+        }
+        result
+    }
+}
+
+impl MealyMachine<String> {
+    pub fn open_mealy_file(path: &str) -> Result<Self, ()> {
+        let mut file1 = File::open(path);
+        if file1.is_err() {
+            file1 = File::open(format!("{path}.mealy"));
+        }
+        let mut contents1 = String::new();
+        match file1 {
+            Ok(mut file) => {
+                file.read_to_string(&mut contents1).map_err(|_| ())?;
+                Ok(MealyMachine::from_string(contents1))
+            }
+            Err(_) => Err(()),
+        }
+    }
+
+    pub fn from_string(s: String) -> Self {
+        let mut result = MealyMachine {
+            alphabet: vec![],
+            states: 0,
+            transition: HashMap::new(),
+            output: HashMap::new(),
+        };
+        for (i, line) in s.split('\n').enumerate() {
+            let line = line.split('%').next().unwrap();
+            match i {
+                0 => result.states = line.trim().parse::<i32>().unwrap_or(0),
+                1 => {
+                    for s in line.split(',') {
+                        if let Some(a) = s.trim().chars().next() {
+                            result.alphabet.push(a);
+                        }
+                    }
+                }
+                _ => {
+                    for (j, s) in line.split(',').enumerate() {
+                        if j >= result.alphabet.len() {
+                            continue;
+                        }
+                        let (target, output) = match s.split_once('/') {
+                            Some(a) => a,
+                            None => continue,
+                        };
+                        if let Ok(a) = target.trim().parse::<i32>() {
+                            let key = (i as i32 - 2, result.alphabet[j]);
+                            result.transition.insert(key, a);
+                            result
+                                .output
+                                .insert(key, String::parse(output.trim()).unwrap_or_default());
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    pub fn to_string(&self) -> String {
+        let mut s = String::new();
+        s.push_str(&self.states.to_string());
+        s.push_str(" %states\n");
+        for char in &self.alphabet {
+            s.push(',');
+            s.push(*char);
+        }
+        s.push_str(" %alphabet\n");
+        for i in 0..self.states {
+            for char in &self.alphabet {
+                s.push(',');
+                match (self.transition.get(&(i, *char)), self.output.get(&(i, *char))) {
+                    (Some(to), Some(output)) => {
+                        s.push_str(&to.to_string());
+                        s.push('/');
+                        s.push_str(&output.to_text());
+                    }
+                    _ => s.push('-'),
+                }
+            }
+            s.push_str(" %");
+            s.push_str(&i.to_string());
+            s.push_str(":\n");
+        }
+        s.replace("\n,", "\n")
+    }
+}
+
+impl Display for MealyMachine<String> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
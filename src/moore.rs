@@ -0,0 +1,273 @@
+use std::{collections::HashMap, fmt::Display, fs::File, hash::Hash, io::Read};
+
+use crate::{
+    dfa::{DFAEvaluationError, Symbol, DFA},
+    mealy::MealyMachine,
+};
+
+#[derive(Debug, Clone)]
+pub struct MooreMachine<O> {
+    pub alphabet: Vec<char>,
+    pub states: i32,
+    pub output: Vec<O>,
+    pub transition: HashMap<(i32, char), i32>,
+}
+
+impl<O: Clone> MooreMachine<O> {
+    pub fn step(&self, state: i32, char: char) -> Result<i32, DFAEvaluationError> {
+        use DFAEvaluationError::*;
+        if !self.alphabet.contains(&char) {
+            return Err(InvalidChar(char));
+        }
+        if state < 0 || state >= self.states {
+            return Err(InvalidState(state));
+        }
+        match self.transition.get(&(state, char)) {
+            Some(&a) if a < 0 || a >= self.states => Err(InvalidState(a)),
+            Some(&a) => Ok(a),
+            None => Err(NoTransition((state, char))),
+        }
+    }
+
+    pub fn run(&self, string: &str) -> Result<Vec<O>, DFAEvaluationError> {
+        if self.states == 0 {
+            return Err(DFAEvaluationError::InvalidState(0));
+        }
+        let mut state = 0;
+        let mut result = vec![self.output[0].clone()];
+        for char in string.chars() {
+            state = self.step(state, char)?;
+            result.push(self.output[state as usize].clone());
+        }
+        Ok(result)
+    }
+
+    pub fn to_mealy(&self) -> MealyMachine<O> {
+        let mut result = MealyMachine {
+            alphabet: self.alphabet.clone(),
+            states: self.states,
+            transition: self.transition.clone(),
+            output: HashMap::new(),
+        };
+        for (&(state, char), &target) in &self.transition {
+            result
+                .output
+                .insert((state, char), self.output[target as usize].clone());
+        }
+        result
+    }
+}
+
+impl<O: Clone + Eq + Hash> MooreMachine<O> {
+    pub fn minimize(&self) -> Self {
+        if self.states == 0 {
+            return self.clone();
+        }
+        let reachable = reachable_states(self.states, &self.alphabet, &self.transition);
+        let mut ids: HashMap<&O, usize> = HashMap::new();
+        let initial: Vec<usize> = reachable
+            .iter()
+            .map(|&i| {
+                let next = ids.len();
+                *ids.entry(&self.output[i as usize]).or_insert(next)
+            })
+            .collect();
+        let (classes, count) = refine_partition(&reachable, initial, &self.alphabet, &self.transition);
+        let mut result = MooreMachine {
+            alphabet: self.alphabet.clone(),
+            states: count as i32,
+            output: vec![self.output[0].clone(); count],
+            transition: HashMap::new(),
+        };
+        for &state in &reachable {
+            let class = classes[&state];
+            result.output[class as usize] = self.output[state as usize].clone();
+            for &char in &self.alphabet {
+                if let Some(target) = self.transition.get(&(state, char)) {
+                    result.transition.insert((class, char), classes[target]);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl MooreMachine<bool> {
+    pub fn from_dfa(dfa: &DFA) -> Self {
+        let dfa = dfa.start_at_zero().complete();
+        MooreMachine {
+            alphabet: dfa.alphabet.clone(),
+            states: dfa.states,
+            output: dfa.accepting.clone(),
+            transition: dfa.transition.clone(),
+        }
+    }
+
+    pub fn to_dfa(&self) -> DFA {
+        DFA {
+            alphabet: self.alphabet.clone(),
+            states: self.states,
+            start: 0,
+            accepting: self.output.clone(),
+            transition: self.transition.clone(),
+            names: HashMap::new(),
+            partial: false,
+        }
+    }
+}
+
+impl MooreMachine<String> {
+    pub fn open_moore_file(path: &str) -> Result<Self, ()> {
+        let mut file1 = File::open(path);
+        if file1.is_err() {
+            file1 = File::open(format!("{path}.moore"));
+        }
+        let mut contents1 = String::new();
+        match file1 {
+            Ok(mut file) => {
+                file.read_to_string(&mut contents1).map_err(|_| ())?;
+                Ok(MooreMachine::from_string(contents1))
+            }
+            Err(_) => Err(()),
+        }
+    }
+
+    pub fn from_string(s: String) -> Self {
+        let mut result = MooreMachine {
+            alphabet: vec![],
+            states: 0,
+            output: vec![],
+            transition: HashMap::new(),
+        };
+        for (i, line) in s.split('\n').enumerate() {
+            let line = line.split('%').next().unwrap();
+            match i {
+                0 => {
+                    result.states = line.trim().parse::<i32>().unwrap_or(0);
+                    result.output = vec![String::new(); result.states.max(0) as usize];
+                }
+                1 => {
+                    for (j, s) in line.split(',').enumerate() {
+                        if j < result.output.len() {
+                            result.output[j] = String::parse(s.trim()).unwrap_or_default();
+                        }
+                    }
+                }
+                2 => {
+                    for s in line.split(',') {
+                        if let Some(a) = s.trim().chars().next() {
+                            result.alphabet.push(a);
+                        }
+                    }
+                }
+                _ => {
+                    for (j, s) in line.split(',').enumerate() {
+                        if j < result.alphabet.len() {
+                            if let Ok(a) = s.trim().parse::<i32>() {
+                                result.transition.insert((i as i32 - 3, result.alphabet[j]), a);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    pub fn to_string(&self) -> String {
+        let mut s = String::new();
+        s.push_str(&self.states.to_string());
+        s.push_str(" %states\n");
+        s.push_str(&self.output.iter().map(|o| o.to_text()).collect::<Vec<_>>().join(","));
+        s.push_str(" %outputs");
+        // the rows are written with a leading comma that is removed below, an empty
+        // first output must keep its comma
+        let mut rows = String::from("\n");
+        for char in &self.alphabet {
+            rows.push(',');
+            rows.push(*char);
+        }
+        rows.push_str(" %alphabet\n");
+        for i in 0..self.states {
+            for char in &self.alphabet {
+                rows.push(',');
+                match self.transition.get(&(i, *char)) {
+                    Some(to) => rows.push_str(&to.to_string()),
+                    None => rows.push('-'),
+                }
+            }
+            rows.push_str(" %");
+            rows.push_str(&i.to_string());
+            rows.push_str(":\n");
+        }
+        s + &rows.replace("\n,", "\n")
+    }
+}
+
+impl Display for MooreMachine<String> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+pub(crate) fn reachable_states(states: i32, alphabet: &[char], transition: &HashMap<(i32, char), i32>) -> Vec<i32> {
+    let mut seen = vec![false; states as usize];
+    let mut result = vec![0];
+    seen[0] = true;
+    let mut next = 0;
+    while next < result.len() {
+        for char in alphabet {
+            if let Some(&target) = transition.get(&(result[next], *char)) {
+                if !seen[target as usize] {
+                    seen[target as usize] = true;
+                    result.push(target);
+                }
+            }
+        }
+        next += 1;
+    }
+    result
+}
+
+// splits states with equal initial classes apart until their successors agree as well;
+// classes are numbered in order of first appearance so the start state stays 0
+pub(crate) fn refine_partition(
+    states: &[i32],
+    mut classes: Vec<usize>,
+    alphabet: &[char],
+    transition: &HashMap<(i32, char), i32>,
+) -> (HashMap<i32, i32>, usize) {
+    let position: HashMap<i32, usize> = states.iter().enumerate().map(|(i, &s)| (s, i)).collect();
+    let mut count = 0;
+    loop {
+        let mut ids: HashMap<Vec<usize>, usize> = HashMap::new();
+        let refined: Vec<usize> = states
+            .iter()
+            .enumerate()
+            .map(|(i, &state)| {
+                let mut signature = vec![classes[i]];
+                for char in alphabet {
+                    signature.push(match transition.get(&(state, *char)) {
+                        Some(target) => classes[position[target]],
+                        None => usize::MAX,
+                    });
+                }
+                let next = ids.len();
+                *ids.entry(signature).or_insert(next)
+            })
+            .collect();
+        classes = refined;
+        if ids.len() == count {
+            break;
+        }
+        count = ids.len();
+    }
+    (
+        states
+            .iter()
+            .enumerate()
+            .map(|(i, &s)| (s, classes[i] as i32))
+            .collect(),
+        count,
+    )
+}
@@ -0,0 +1,334 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs::File,
+    io::Read,
+};
+
+const SEARCH_LIMIT: usize = 100_000;
+
+pub type Move = (i32, String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Acceptance {
+    FinalState,
+    EmptyStack,
+}
+
+#[derive(Debug, Clone)]
+pub struct PDA {
+    pub alphabet: Vec<char>,
+    pub stack_alphabet: Vec<char>,
+    pub states: i32,
+    pub accepting: Vec<bool>,
+    pub acceptance: Acceptance,
+    pub transition: HashMap<(i32, Option<char>, char), Vec<Move>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Configuration {
+    pub state: i32,
+    pub position: usize,
+    pub stack: String,
+}
+
+#[derive(Debug)]
+pub enum PDAEvaluationError {
+    InvalidChar(char),
+    SearchLimitReached(usize),
+}
+
+impl Default for PDA {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PDA {
+    pub fn new() -> Self {
+        Self {
+            alphabet: Vec::new(),
+            stack_alphabet: Vec::new(),
+            states: 0,
+            accepting: Vec::new(),
+            acceptance: Acceptance::FinalState,
+            transition: HashMap::new(),
+        }
+    }
+
+    pub fn add_transition(&mut self, from: i32, input: Option<char>, top: char, to: i32, push: &str) {
+        self.transition
+            .entry((from, input, top))
+            .or_default()
+            .push((to, push.to_string()));
+    }
+
+    pub fn open_pda_file(path: &str) -> Result<PDA, ()> {
+        let mut file1 = File::open(path);
+        if file1.is_err() {
+            file1 = File::open(format!("{path}.pda"));
+        }
+        let mut contents1 = String::new();
+        match file1 {
+            Ok(mut file) => {
+                file.read_to_string(&mut contents1).map_err(|_| ())?;
+                Ok(PDA::from_string(contents1))
+            }
+            Err(_) => Err(()),
+        }
+    }
+
+    pub fn from_string(s: String) -> Self {
+        let mut result = PDA::new();
+        for (i, line) in s.split('\n').enumerate() {
+            let line = line.split('%').next().unwrap();
+            match i {
+                0 => match line.trim().parse::<i32>() {
+                    Ok(a) => {
+                        result.states = a;
+                        result.accepting = vec![false; a as usize]
+                    }
+                    Err(_) => result.states = 0,
+                },
+                1 => {
+                    for s in line.split(',') {
+                        if let Ok(a) = s.trim().parse::<usize>() {
+                            if a < result.accepting.len() {
+                                result.accepting[a] = true;
+                            }
+                        }
+                    }
+                }
+                2 | 3 => {
+                    for s in line.split(',') {
+                        if let Some(a) = s.trim().chars().next() {
+                            if i == 2 {
+                                result.alphabet.push(a);
+                            } else {
+                                result.stack_alphabet.push(a);
+                            }
+                        }
+                    }
+                }
+                4 => {
+                    if line.trim().to_lowercase().starts_with("empty") {
+                        result.acceptance = Acceptance::EmptyStack;
+                    }
+                }
+                _ => {
+                    let fields: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+                    if fields.len() != 5 {
+                        continue;
+                    }
+                    let (from, to) = match (fields[0].parse::<i32>(), fields[3].parse::<i32>()) {
+                        (Ok(from), Ok(to)) => (from, to),
+                        _ => continue,
+                    };
+                    let top = match fields[2].chars().next() {
+                        Some(a) => a,
+                        None => continue,
+                    };
+                    result.add_transition(from, fields[1].chars().next(), top, to, fields[4]);
+                }
+            }
+        }
+        result
+    }
+
+    pub fn to_string(&self) -> String {
+        let mut s = String::new();
+        s.push_str(&self.states.to_string());
+        s.push_str(" %states\n");
+        for i in 0..self.states {
+            if self.accepting[i as usize] {
+                s.push(',');
+                s.push_str(&i.to_string());
+            }
+        }
+        s.push_str(" %accepting states\n");
+        for char in &self.alphabet {
+            s.push(',');
+            s.push(*char);
+        }
+        s.push_str(" %alphabet\n");
+        for char in &self.stack_alphabet {
+            s.push(',');
+            s.push(*char);
+        }
+        s.push_str(" %stack alphabet, the first symbol starts on the stack\n");
+        s.push_str(match self.acceptance {
+            Acceptance::FinalState => "final",
+            Acceptance::EmptyStack => "empty",
+        });
+        s.push_str(" %acceptance\n");
+        let mut keys: Vec<&(i32, Option<char>, char)> = self.transition.keys().collect();
+        keys.sort();
+        for key in keys {
+            for (to, push) in &self.transition[key] {
+                let input = key.1.map(|c| c.to_string()).unwrap_or_default();
+                s.push_str(&format!("{},{input},{},{to},{push}\n", key.0, key.2));
+            }
+        }
+        s.replace("\n,", "\n")
+    }
+
+    // runs start with a fresh bottom symbol under the original one and every accepting
+    // state can drain the stack, so the stack only empties on runs the original accepts
+    pub fn to_empty_stack_acceptance(&self) -> Self {
+        if self.acceptance == Acceptance::EmptyStack {
+            return self.clone();
+        }
+        let bottom = (0xE000..)
+            .filter_map(char::from_u32)
+            .find(|c| !self.stack_alphabet.contains(c))
+            .unwrap();
+        let drain = self.states + 1;
+        let mut result = PDA::new();
+        result.alphabet = self.alphabet.clone();
+        result.stack_alphabet = vec![bottom];
+        result.stack_alphabet.extend(self.stack_alphabet.clone());
+        result.states = self.states + 2;
+        result.accepting = vec![false; result.states as usize];
+        result.acceptance = Acceptance::EmptyStack;
+        if let Some(&start) = self.stack_alphabet.first() {
+            result.add_transition(0, None, bottom, 1, &format!("{start}{bottom}"));
+        }
+        for (&(from, input, top), moves) in &self.transition {
+            for (to, push) in moves {
+                result.add_transition(from + 1, input, top, to + 1, push);
+            }
+        }
+        for top in result.stack_alphabet.clone() {
+            for state in 0..self.states {
+                if self.accepting[state as usize] {
+                    result.add_transition(state + 1, None, top, drain, "");
+                }
+            }
+            result.add_transition(drain, None, top, drain, "");
+        }
+        result
+    }
+
+    fn is_accepting(&self, config: &Configuration, length: usize) -> bool {
+        config.position == length
+            && match self.acceptance {
+                Acceptance::FinalState => self.accepting[config.state as usize],
+                Acceptance::EmptyStack => config.stack.is_empty(),
+            }
+    }
+
+    fn successors(&self, config: &Configuration, input: &[char]) -> Vec<Configuration> {
+        let mut result = vec![];
+        let top = match config.stack.chars().next() {
+            Some(a) => a,
+            None => return result,
+        };
+        let rest = &config.stack[top.len_utf8()..];
+        let mut moves = vec![(None, config.position)];
+        if config.position < input.len() {
+            moves.push((Some(input[config.position]), config.position + 1));
+        }
+        for (char, position) in moves {
+            if let Some(targets) = self.transition.get(&(config.state, char, top)) {
+                for (state, push) in targets {
+                    result.push(Configuration {
+                        state: *state,
+                        position,
+                        stack: format!("{push}{rest}"),
+                    });
+                }
+            }
+        }
+        result
+    }
+
+    pub fn find_run(&self, string: &str) -> Result<Option<Vec<Configuration>>, PDAEvaluationError> {
+        self.find_run_with_limit(string, SEARCH_LIMIT)
+    }
+
+    // breadth-first search over configurations, so the reported run is a shortest one;
+    // epsilon moves that keep pushing can make the search space infinite, hence the limit
+    pub fn find_run_with_limit(
+        &self,
+        string: &str,
+        limit: usize,
+    ) -> Result<Option<Vec<Configuration>>, PDAEvaluationError> {
+        let input: Vec<char> = string.chars().collect();
+        for char in &input {
+            if !self.alphabet.contains(char) {
+                return Err(PDAEvaluationError::InvalidChar(*char));
+            }
+        }
+        if self.states == 0 {
+            return Ok(None);
+        }
+        let start = Configuration {
+            state: 0,
+            position: 0,
+            stack: self.stack_alphabet.first().map(|c| c.to_string()).unwrap_or_default(),
+        };
+        let mut seen = HashSet::new();
+        seen.insert(start.clone());
+        let mut queue = vec![(start, usize::MAX)];
+        let mut next = 0;
+        while next < queue.len() {
+            if next >= limit {
+                return Err(PDAEvaluationError::SearchLimitReached(limit));
+            }
+            let config = queue[next].0.clone();
+            if self.is_accepting(&config, input.len()) {
+                let mut run = vec![];
+                let mut i = next;
+                while i != usize::MAX {
+                    run.push(queue[i].0.clone());
+                    i = queue[i].1;
+                }
+                run.reverse();
+                return Ok(Some(run));
+            }
+            for successor in self.successors(&config, &input) {
+                if seen.insert(successor.clone()) {
+                    queue.push((successor, next));
+                }
+            }
+            next += 1;
+        }
+        Ok(None)
+    }
+
+    pub fn evaluate(&self, string: &str) -> Result<bool, PDAEvaluationError> {
+        Ok(self.find_run(string)?.is_some())
+    }
+
+    pub fn evaluate_to_string(&self, s: &str) -> String {
+        use PDAEvaluationError::*;
+        match self.evaluate(s) {
+            Ok(true) => String::from("true"),
+            Ok(false) => String::from("false"),
+            Err(InvalidChar(c)) => format!("invalid character: {c}"),
+            Err(SearchLimitReached(n)) => format!("no accepting run found within {n} configurations"),
+        }
+    }
+
+    pub fn trace_to_string(&self, s: &str) -> String {
+        match self.find_run(s) {
+            Ok(Some(run)) => {
+                let input: Vec<char> = s.chars().collect();
+                let mut result = String::from("step\tstate\tremaining\tstack\n");
+                for (i, config) in run.iter().enumerate() {
+                    let remaining: String = input[config.position..].iter().collect();
+                    result.push_str(&format!("{i}\t{}\t{remaining}\t{}\n", config.state, config.stack));
+                }
+                result.push_str("true");
+                result
+            }
+            _ => self.evaluate_to_string(s),
+        }
+    }
+}
+
+impl Display for PDA {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
@@ -0,0 +1,415 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::dfa::DFA;
+
+const APPLY_LIMIT: usize = 100_000;
+const DETERMINIZE_LIMIT: usize = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct Transducer {
+    pub input_alphabet: Vec<char>,
+    pub output_alphabet: Vec<char>,
+    pub states: i32,
+    pub accepting: Vec<bool>,
+    pub transition: Vec<(i32, Option<char>, String, i32)>,
+}
+
+#[derive(Debug)]
+pub enum TransducerError {
+    NotSequential,
+    TooManyStates,
+}
+
+impl Default for Transducer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transducer {
+    pub fn new() -> Self {
+        Self {
+            input_alphabet: Vec::new(),
+            output_alphabet: Vec::new(),
+            states: 0,
+            accepting: Vec::new(),
+            transition: Vec::new(),
+        }
+    }
+
+    pub fn identity(alph: &[char]) -> Self {
+        Self {
+            input_alphabet: alph.to_vec(),
+            output_alphabet: alph.to_vec(),
+            states: 1,
+            accepting: vec![true],
+            transition: alph.iter().map(|&c| (0, Some(c), c.to_string(), 0)).collect(),
+        }
+    }
+
+    pub fn add_state(&mut self, accepting: bool) -> i32 {
+        self.states += 1;
+        self.accepting.push(accepting);
+        self.states - 1
+    }
+
+    pub fn add_transition(&mut self, from: i32, input: Option<char>, output: &str, to: i32) {
+        if let Some(c) = input {
+            if !self.input_alphabet.contains(&c) {
+                self.input_alphabet.push(c);
+            }
+        }
+        for c in output.chars() {
+            if !self.output_alphabet.contains(&c) {
+                self.output_alphabet.push(c);
+            }
+        }
+        self.transition.push((from, input, output.to_string(), to));
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        let mut seen = HashSet::new();
+        self.transition
+            .iter()
+            .all(|(from, input, _, _)| input.is_some() && seen.insert((*from, *input)))
+    }
+
+    pub fn apply(&self, string: &str) -> Vec<String> {
+        self.apply_with_limit(string, APPLY_LIMIT)
+    }
+
+    // output-producing epsilon cycles make the set of outputs infinite, so the search
+    // stops after `limit` configurations and returns what it has found so far
+    pub fn apply_with_limit(&self, string: &str, limit: usize) -> Vec<String> {
+        let chars: Vec<char> = string.chars().collect();
+        let mut result = vec![];
+        if self.states == 0 {
+            return result;
+        }
+        let mut seen = HashSet::new();
+        let mut queue = vec![(0, 0, String::new())];
+        seen.insert(queue[0].clone());
+        let mut next = 0;
+        while next < queue.len() && next < limit {
+            let (state, position, output) = queue[next].clone();
+            next += 1;
+            if position == chars.len() && self.accepting[state as usize] && !result.contains(&output) {
+                result.push(output.clone());
+            }
+            for (from, input, out, to) in &self.transition {
+                if *from != state {
+                    continue;
+                }
+                let position = match input {
+                    None => position,
+                    Some(c) if position < chars.len() && chars[position] == *c => position + 1,
+                    _ => continue,
+                };
+                let config = (*to, position, format!("{output}{out}"));
+                if seen.insert(config.clone()) {
+                    queue.push(config);
+                }
+            }
+        }
+        result.sort();
+        result
+    }
+
+    // splits every transition so that it writes at most one output character
+    fn normalize(&self) -> Self {
+        let mut result = self.clone();
+        result.transition = vec![];
+        for (from, input, output, to) in &self.transition {
+            let chars: Vec<char> = output.chars().collect();
+            if chars.len() <= 1 {
+                result.transition.push((*from, *input, output.clone(), *to));
+                continue;
+            }
+            let mut state = *from;
+            for (i, c) in chars.iter().enumerate() {
+                let target = if i == chars.len() - 1 {
+                    *to
+                } else {
+                    result.add_state(false)
+                };
+                let input = if i == 0 { *input } else { None };
+                result.transition.push((state, input, c.to_string(), target));
+                state = target;
+            }
+        }
+        result
+    }
+
+    pub fn invert(&self) -> Self {
+        let normalized = self.normalize();
+        Transducer {
+            input_alphabet: self.output_alphabet.clone(),
+            output_alphabet: self.input_alphabet.clone(),
+            states: normalized.states,
+            accepting: normalized.accepting.clone(),
+            transition: normalized
+                .transition
+                .iter()
+                .map(|(from, input, output, to)| {
+                    (
+                        *from,
+                        output.chars().next(),
+                        input.map(|c| c.to_string()).unwrap_or_default(),
+                        *to,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    // feeds the output of `self` into `rhs`
+    pub fn compose(&self, rhs: &Self) -> Self {
+        let lhs = self.normalize();
+        let mut result = Transducer {
+            input_alphabet: self.input_alphabet.clone(),
+            output_alphabet: rhs.output_alphabet.clone(),
+            states: 0,
+            accepting: vec![],
+            transition: vec![],
+        };
+        if lhs.states == 0 || rhs.states == 0 {
+            return result;
+        }
+        let mut index: HashMap<(i32, i32), i32> = HashMap::new();
+        let mut queue = vec![(0, 0)];
+        index.insert((0, 0), 0);
+        let mut next = 0;
+        while next < queue.len() {
+            let (p, q) = queue[next];
+            result
+                .accepting
+                .push(lhs.accepting[p as usize] && rhs.accepting[q as usize]);
+            let mut edges = vec![];
+            for (from, input, output, to) in &lhs.transition {
+                if *from != p {
+                    continue;
+                }
+                match output.chars().next() {
+                    None => edges.push((*input, String::new(), (*to, q))),
+                    Some(c) => {
+                        for (from2, input2, output2, to2) in &rhs.transition {
+                            if *from2 == q && *input2 == Some(c) {
+                                edges.push((*input, output2.clone(), (*to, *to2)));
+                            }
+                        }
+                    }
+                }
+            }
+            for (from2, input2, output2, to2) in &rhs.transition {
+                if *from2 == q && input2.is_none() {
+                    edges.push((None, output2.clone(), (p, *to2)));
+                }
+            }
+            for (input, output, target) in edges {
+                let id = match index.get(&target) {
+                    Some(&id) => id,
+                    None => {
+                        let id = queue.len() as i32;
+                        index.insert(target, id);
+                        queue.push(target);
+                        id
+                    }
+                };
+                result.transition.push((next as i32, input, output, id));
+            }
+            next += 1;
+        }
+        result.states = queue.len() as i32;
+        result
+    }
+
+    pub fn restrict_domain(&self, dfa: &DFA) -> Self {
+        let mut result = Transducer {
+            input_alphabet: self.input_alphabet.clone(),
+            output_alphabet: self.output_alphabet.clone(),
+            states: 0,
+            accepting: vec![],
+            transition: vec![],
+        };
+        if self.states == 0 || dfa.states == 0 {
+            return result;
+        }
+        let mut index: HashMap<(i32, i32), i32> = HashMap::new();
+        let mut queue = vec![(0, dfa.start)];
+        index.insert((0, dfa.start), 0);
+        let mut next = 0;
+        while next < queue.len() {
+            let (p, d) = queue[next];
+            result
+                .accepting
+                .push(self.accepting[p as usize] && dfa.accepting[d as usize]);
+            for (from, input, output, to) in &self.transition {
+                if *from != p {
+                    continue;
+                }
+                let target = match input {
+                    None => (*to, d),
+                    Some(c) => match dfa.transition.get(&(d, *c)) {
+                        Some(&d2) => (*to, d2),
+                        None => continue,
+                    },
+                };
+                let id = match index.get(&target) {
+                    Some(&id) => id,
+                    None => {
+                        let id = queue.len() as i32;
+                        index.insert(target, id);
+                        queue.push(target);
+                        id
+                    }
+                };
+                result.transition.push((next as i32, *input, output.clone(), id));
+            }
+            next += 1;
+        }
+        result.states = queue.len() as i32;
+        result
+    }
+
+    pub fn domain(&self) -> DFA {
+        let edges: Vec<(i32, Option<char>, i32)> = self
+            .transition
+            .iter()
+            .map(|(from, input, _, to)| (*from, *input, *to))
+            .collect();
+        self.to_dfa(&self.input_alphabet, &self.accepting, &edges)
+    }
+
+    pub fn range(&self) -> DFA {
+        let normalized = self.normalize();
+        let edges: Vec<(i32, Option<char>, i32)> = normalized
+            .transition
+            .iter()
+            .map(|(from, _, output, to)| (*from, output.chars().next(), *to))
+            .collect();
+        self.to_dfa(&self.output_alphabet, &normalized.accepting, &edges)
+    }
+
+    pub fn range_restricted_to(&self, dfa: &DFA) -> DFA {
+        self.restrict_domain(dfa).range()
+    }
+
+    fn to_dfa(&self, alphabet: &[char], accepting: &[bool], edges: &[(i32, Option<char>, i32)]) -> DFA {
+        if self.states == 0 {
+            let mut result = DFA::new();
+            result.alphabet = alphabet.to_vec();
+            result.states = 1;
+            result.accepting = vec![false];
+            result.transition.extend(alphabet.iter().map(|&c| ((0, c), 0)));
+            return result;
+        }
+        DFA::from_nfa(alphabet, accepting, edges)
+    }
+
+    // residual-output subset construction; fails when the relation is not a
+    // sequential function, which shows up as a state needing two different residuals
+    // or as the construction never closing
+    pub fn determinize(&self) -> Result<Self, TransducerError> {
+        let mut result = Transducer {
+            input_alphabet: self.input_alphabet.clone(),
+            output_alphabet: self.output_alphabet.clone(),
+            states: 0,
+            accepting: vec![],
+            transition: vec![],
+        };
+        if self.states == 0 {
+            return Ok(result);
+        }
+        let start = self.residual_closure(vec![(0, String::new())])?;
+        let mut index: HashMap<Vec<(i32, String)>, i32> = HashMap::new();
+        let mut queue = vec![start.clone()];
+        index.insert(start, 0);
+        let mut next = 0;
+        while next < queue.len() {
+            if queue.len() > DETERMINIZE_LIMIT {
+                return Err(TransducerError::TooManyStates);
+            }
+            let set = queue[next].clone();
+            let mut accepting = false;
+            for (state, residual) in &set {
+                if self.accepting[*state as usize] {
+                    if !residual.is_empty() {
+                        return Err(TransducerError::NotSequential);
+                    }
+                    accepting = true;
+                }
+            }
+            result.accepting.push(accepting);
+            for &char in &self.input_alphabet {
+                let mut target = vec![];
+                for (state, residual) in &set {
+                    for (from, input, output, to) in &self.transition {
+                        if from == state && *input == Some(char) {
+                            target.push((*to, format!("{residual}{output}")));
+                        }
+                    }
+                }
+                if target.is_empty() {
+                    continue;
+                }
+                let mut target = self.residual_closure(target)?;
+                let prefix = common_prefix(target.iter().map(|(_, r)| r.as_str()));
+                for (_, residual) in target.iter_mut() {
+                    *residual = residual[prefix.len()..].to_string();
+                }
+                let id = match index.get(&target) {
+                    Some(&id) => id,
+                    None => {
+                        let id = queue.len() as i32;
+                        index.insert(target.clone(), id);
+                        queue.push(target);
+                        id
+                    }
+                };
+                result.transition.push((next as i32, Some(char), prefix, id));
+            }
+            next += 1;
+        }
+        result.states = queue.len() as i32;
+        Ok(result)
+    }
+
+    fn residual_closure(&self, mut set: Vec<(i32, String)>) -> Result<Vec<(i32, String)>, TransducerError> {
+        let mut next = 0;
+        while next < set.len() {
+            let (state, residual) = set[next].clone();
+            for (from, input, output, to) in &self.transition {
+                if *from != state || input.is_some() {
+                    continue;
+                }
+                let pair = (*to, format!("{residual}{output}"));
+                match set.iter().find(|(s, _)| *s == pair.0) {
+                    Some(existing) if *existing == pair => continue,
+                    Some(_) => return Err(TransducerError::NotSequential),
+                    None => set.push(pair),
+                }
+            }
+            next += 1;
+        }
+        set.sort();
+        set.dedup();
+        for pair in set.windows(2) {
+            if pair[0].0 == pair[1].0 {
+                return Err(TransducerError::NotSequential);
+            }
+        }
+        Ok(set)
+    }
+}
+
+fn common_prefix<'a>(mut strings: impl Iterator<Item = &'a str>) -> String {
+    let mut prefix: Vec<char> = match strings.next() {
+        Some(s) => s.chars().collect(),
+        None => return String::new(),
+    };
+    for s in strings {
+        let length = prefix.iter().zip(s.chars()).take_while(|(a, b)| **a == *b).count();
+        prefix.truncate(length);
+    }
+    prefix.iter().collect()
+}
@@ -0,0 +1,315 @@
+use std::{collections::HashMap, fmt::Display, fs::File, io::Read};
+
+pub const STEP_LIMIT: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Stay,
+}
+
+#[derive(Debug, Clone)]
+pub struct TuringMachine {
+    pub alphabet: Vec<char>,
+    pub tape_alphabet: Vec<char>,
+    pub blank: char,
+    pub states: i32,
+    pub accepting: Vec<bool>,
+    pub rejecting: Vec<bool>,
+    pub transition: HashMap<(i32, char), (i32, char, Direction)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TMResult {
+    Accept,
+    Reject,
+    Halt,
+    Timeout,
+}
+
+#[derive(Debug, Clone)]
+pub struct TapeSnapshot {
+    pub state: i32,
+    pub head: usize,
+    pub tape: Vec<char>,
+}
+
+#[derive(Debug)]
+pub enum TMEvaluationError {
+    InvalidChar(char),
+    InvalidState(i32),
+}
+
+impl Default for TuringMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TuringMachine {
+    pub fn new() -> Self {
+        Self {
+            alphabet: Vec::new(),
+            tape_alphabet: Vec::new(),
+            blank: '_',
+            states: 0,
+            accepting: Vec::new(),
+            rejecting: Vec::new(),
+            transition: HashMap::new(),
+        }
+    }
+
+    pub fn open_tm_file(path: &str) -> Result<TuringMachine, ()> {
+        let mut file1 = File::open(path);
+        if file1.is_err() {
+            file1 = File::open(format!("{path}.tm"));
+        }
+        let mut contents1 = String::new();
+        match file1 {
+            Ok(mut file) => {
+                file.read_to_string(&mut contents1).map_err(|_| ())?;
+                Ok(TuringMachine::from_string(contents1))
+            }
+            Err(_) => Err(()),
+        }
+    }
+
+    pub fn from_string(s: String) -> Self {
+        let mut result = TuringMachine::new();
+        for (i, line) in s.split('\n').enumerate() {
+            let line = line.split('%').next().unwrap();
+            match i {
+                0 => match line.trim().parse::<i32>() {
+                    Ok(a) => {
+                        result.states = a;
+                        result.accepting = vec![false; a as usize];
+                        result.rejecting = vec![false; a as usize];
+                    }
+                    Err(_) => result.states = 0,
+                },
+                1 | 2 => {
+                    for s in line.split(',') {
+                        if let Ok(a) = s.trim().parse::<usize>() {
+                            if a < result.accepting.len() {
+                                if i == 1 {
+                                    result.accepting[a] = true;
+                                } else {
+                                    result.rejecting[a] = true;
+                                }
+                            }
+                        }
+                    }
+                }
+                3 | 4 => {
+                    for s in line.split(',') {
+                        if let Some(a) = s.trim().chars().next() {
+                            if i == 3 {
+                                result.alphabet.push(a);
+                            } else {
+                                result.tape_alphabet.push(a);
+                            }
+                        }
+                    }
+                    if i == 4 {
+                        if let Some(&a) = result.tape_alphabet.first() {
+                            result.blank = a;
+                        }
+                    }
+                }
+                _ => {
+                    let fields: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+                    if fields.len() != 5 {
+                        continue;
+                    }
+                    let (from, to) = match (fields[0].parse::<i32>(), fields[2].parse::<i32>()) {
+                        (Ok(from), Ok(to)) => (from, to),
+                        _ => continue,
+                    };
+                    let (read, write) = match (fields[1].chars().next(), fields[3].chars().next()) {
+                        (Some(read), Some(write)) => (read, write),
+                        _ => continue,
+                    };
+                    let direction = match fields[4].to_uppercase().as_str() {
+                        "L" => Direction::Left,
+                        "R" => Direction::Right,
+                        "S" | "N" => Direction::Stay,
+                        _ => continue,
+                    };
+                    result.transition.insert((from, read), (to, write, direction));
+                }
+            }
+        }
+        result
+    }
+
+    pub fn to_string(&self) -> String {
+        let mut s = String::new();
+        s.push_str(&self.states.to_string());
+        s.push_str(" %states\n");
+        for (flags, comment) in [
+            (&self.accepting, " %accepting states\n"),
+            (&self.rejecting, " %rejecting states\n"),
+        ] {
+            for i in 0..self.states {
+                if flags[i as usize] {
+                    s.push(',');
+                    s.push_str(&i.to_string());
+                }
+            }
+            s.push_str(comment);
+        }
+        for char in &self.alphabet {
+            s.push(',');
+            s.push(*char);
+        }
+        s.push_str(" %input alphabet\n");
+        s.push(self.blank);
+        for char in &self.tape_alphabet {
+            if *char != self.blank {
+                s.push(',');
+                s.push(*char);
+            }
+        }
+        s.push_str(" %tape alphabet, the first symbol is the blank\n");
+        let mut keys: Vec<&(i32, char)> = self.transition.keys().collect();
+        keys.sort();
+        for key in keys {
+            let (to, write, direction) = self.transition[key];
+            let direction = match direction {
+                Direction::Left => 'L',
+                Direction::Right => 'R',
+                Direction::Stay => 'S',
+            };
+            s.push_str(&format!("{},{},{to},{write},{direction}\n", key.0, key.1));
+        }
+        s.replace("\n,", "\n")
+    }
+
+    pub fn run(&self, string: &str, max_steps: usize) -> Result<TMResult, TMEvaluationError> {
+        Ok(self.simulate(string, max_steps, false)?.0)
+    }
+
+    pub fn trace(&self, string: &str, max_steps: usize) -> Result<(TMResult, Vec<TapeSnapshot>), TMEvaluationError> {
+        self.simulate(string, max_steps, true)
+    }
+
+    fn simulate(
+        &self,
+        string: &str,
+        max_steps: usize,
+        record: bool,
+    ) -> Result<(TMResult, Vec<TapeSnapshot>), TMEvaluationError> {
+        let mut tape: Vec<char> = string.chars().collect();
+        for char in &tape {
+            if !self.alphabet.contains(char) {
+                return Err(TMEvaluationError::InvalidChar(*char));
+            }
+        }
+        if tape.is_empty() {
+            tape.push(self.blank);
+        }
+        let mut snapshots = vec![];
+        if self.states == 0 {
+            return Ok((TMResult::Halt, snapshots));
+        }
+        let mut state = 0;
+        let mut head = 0;
+        let mut steps = 0;
+        loop {
+            if record {
+                snapshots.push(TapeSnapshot {
+                    state,
+                    head,
+                    tape: tape.clone(),
+                });
+            }
+            if state < 0 || state >= self.states {
+                return Err(TMEvaluationError::InvalidState(state));
+            }
+            if self.accepting[state as usize] {
+                return Ok((TMResult::Accept, snapshots));
+            }
+            if self.rejecting[state as usize] {
+                return Ok((TMResult::Reject, snapshots));
+            }
+            let (to, write, direction) = match self.transition.get(&(state, tape[head])) {
+                Some(&a) => a,
+                None => return Ok((TMResult::Halt, snapshots)),
+            };
+            if steps == max_steps {
+                return Ok((TMResult::Timeout, snapshots));
+            }
+            steps += 1;
+            tape[head] = write;
+            state = to;
+            match direction {
+                Direction::Left if head == 0 => tape.insert(0, self.blank),
+                Direction::Left => head -= 1,
+                Direction::Right => {
+                    head += 1;
+                    if head == tape.len() {
+                        tape.push(self.blank);
+                    }
+                }
+                Direction::Stay => {}
+            }
+        }
+    }
+
+    pub fn evaluate_to_string(&self, s: &str) -> String {
+        match self.run(s, STEP_LIMIT) {
+            Ok(result) => result_to_string(result, STEP_LIMIT),
+            Err(e) => error_to_string(e),
+        }
+    }
+
+    pub fn trace_to_string(&self, s: &str) -> String {
+        match self.trace(s, STEP_LIMIT) {
+            Ok((result, snapshots)) => {
+                let mut string = String::from("step\tstate\ttape\n");
+                for (i, snapshot) in snapshots.iter().enumerate() {
+                    string.push_str(&format!("{i}\t{}\t{}\n", snapshot.state, snapshot));
+                }
+                string.push_str(&result_to_string(result, STEP_LIMIT));
+                string
+            }
+            Err(e) => error_to_string(e),
+        }
+    }
+}
+
+fn error_to_string(error: TMEvaluationError) -> String {
+    match error {
+        TMEvaluationError::InvalidChar(c) => format!("invalid character: {c}"),
+        TMEvaluationError::InvalidState(i) => format!("invalid state: {i}"),
+    }
+}
+
+fn result_to_string(result: TMResult, max_steps: usize) -> String {
+    match result {
+        TMResult::Accept => String::from("true"),
+        TMResult::Reject => String::from("false"),
+        TMResult::Halt => String::from("false (halted without reaching an accepting or rejecting state)"),
+        TMResult::Timeout => format!("no result after {max_steps} steps"),
+    }
+}
+
+impl Display for TuringMachine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+impl Display for TapeSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, char) in self.tape.iter().enumerate() {
+            if i == self.head {
+                write!(f, "[{char}]")?;
+            } else {
+                write!(f, "{char}")?;
+            }
+        }
+        Ok(())
+    }
+}
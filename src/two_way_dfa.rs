@@ -0,0 +1,294 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs::File,
+    io::Read,
+};
+
+use crate::dfa::{DFAEvaluationError, DFA};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TapeSymbol {
+    LeftEnd,
+    Symbol(char),
+    RightEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadMove {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TwoWayResult {
+    Accept,
+    Reject,
+    Loop,
+}
+
+// the input is read as ⊢w⊣ starting on ⊢ in state 0. moving right off ⊣ ends the run
+// and accepts if the state it moves into is accepting; moving left off ⊢ or having no
+// transition rejects
+#[derive(Debug, Clone)]
+pub struct TwoWayDFA {
+    pub alphabet: Vec<char>,
+    pub states: i32,
+    pub accepting: Vec<bool>,
+    pub transition: HashMap<(i32, TapeSymbol), (i32, HeadMove)>,
+}
+
+impl Default for TwoWayDFA {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TwoWayDFA {
+    pub fn new() -> Self {
+        Self {
+            alphabet: Vec::new(),
+            states: 0,
+            accepting: Vec::new(),
+            transition: HashMap::new(),
+        }
+    }
+
+    pub fn open_2dfa_file(path: &str) -> Result<TwoWayDFA, ()> {
+        let mut file1 = File::open(path);
+        if file1.is_err() {
+            file1 = File::open(format!("{path}.2dfa"));
+        }
+        let mut contents1 = String::new();
+        match file1 {
+            Ok(mut file) => {
+                file.read_to_string(&mut contents1).map_err(|_| ())?;
+                Ok(TwoWayDFA::from_string(contents1))
+            }
+            Err(_) => Err(()),
+        }
+    }
+
+    // same layout as a .dfa file, with two extra columns at the end of every row for the
+    // end markers ⊢ and ⊣. every cell is a target state followed by L or R, or empty
+    pub fn from_string(s: String) -> Self {
+        let mut result = TwoWayDFA::new();
+        for (i, line) in s.split('\n').enumerate() {
+            let line = line.split('%').next().unwrap();
+            match i {
+                0 => match line.trim().parse::<i32>() {
+                    Ok(a) => {
+                        result.states = a;
+                        result.accepting = vec![false; a as usize]
+                    }
+                    Err(_) => result.states = 0,
+                },
+                1 => {
+                    for s in line.split(',') {
+                        if let Ok(a) = s.trim().parse::<usize>() {
+                            if a < result.accepting.len() {
+                                result.accepting[a] = true;
+                            }
+                        }
+                    }
+                }
+                2 => {
+                    for s in line.split(',') {
+                        if let Some(a) = s.trim().chars().next() {
+                            result.alphabet.push(a);
+                        }
+                    }
+                }
+                _ => {
+                    let symbols = result.tape_symbols();
+                    for (j, s) in line.split(',').enumerate() {
+                        if j >= symbols.len() {
+                            continue;
+                        }
+                        let s = s.trim();
+                        let direction = match s.chars().last() {
+                            Some('L') | Some('l') => HeadMove::Left,
+                            Some('R') | Some('r') => HeadMove::Right,
+                            _ => continue,
+                        };
+                        if let Ok(a) = s[..s.len() - 1].trim().parse::<i32>() {
+                            result.transition.insert((i as i32 - 3, symbols[j]), (a, direction));
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    fn tape_symbols(&self) -> Vec<TapeSymbol> {
+        let mut result: Vec<TapeSymbol> = self.alphabet.iter().map(|&c| TapeSymbol::Symbol(c)).collect();
+        result.push(TapeSymbol::LeftEnd);
+        result.push(TapeSymbol::RightEnd);
+        result
+    }
+
+    pub fn to_string(&self) -> String {
+        let mut s = String::new();
+        s.push_str(&self.states.to_string());
+        s.push_str(" %states\n");
+        for i in 0..self.states {
+            if self.accepting[i as usize] {
+                s.push(',');
+                s.push_str(&i.to_string());
+            }
+        }
+        s.push_str(" %accepting states\n");
+        for char in &self.alphabet {
+            s.push(',');
+            s.push(*char);
+        }
+        s.push_str(" %alphabet, followed by ⊢ and ⊣ in the transition table\n");
+        for i in 0..self.states {
+            for symbol in self.tape_symbols() {
+                s.push(',');
+                if let Some((to, direction)) = self.transition.get(&(i, symbol)) {
+                    s.push_str(&to.to_string());
+                    s.push(match direction {
+                        HeadMove::Left => 'L',
+                        HeadMove::Right => 'R',
+                    });
+                }
+            }
+            s.push_str(" %");
+            s.push_str(&i.to_string());
+            s.push_str(":\n");
+        }
+        s.replace("\n,", "\n")
+    }
+
+    pub fn simulate(&self, string: &str) -> Result<TwoWayResult, DFAEvaluationError> {
+        let mut tape = vec![TapeSymbol::LeftEnd];
+        for char in string.chars() {
+            if !self.alphabet.contains(&char) {
+                return Err(DFAEvaluationError::InvalidChar(char));
+            }
+            tape.push(TapeSymbol::Symbol(char));
+        }
+        tape.push(TapeSymbol::RightEnd);
+        if self.states == 0 {
+            return Ok(TwoWayResult::Reject);
+        }
+        let mut seen = HashSet::new();
+        let (mut state, mut head) = (0, 0);
+        // a deterministic machine that repeats a configuration never halts
+        while seen.insert((state, head)) {
+            let (to, direction) = match self.transition.get(&(state, tape[head])) {
+                Some(&a) => a,
+                None => return Ok(TwoWayResult::Reject),
+            };
+            if to < 0 || to >= self.states {
+                return Err(DFAEvaluationError::InvalidState(to));
+            }
+            state = to;
+            match direction {
+                HeadMove::Left if head == 0 => return Ok(TwoWayResult::Reject),
+                HeadMove::Left => head -= 1,
+                HeadMove::Right if head == tape.len() - 1 => {
+                    return Ok(match self.accepting[state as usize] {
+                        true => TwoWayResult::Accept,
+                        false => TwoWayResult::Reject,
+                    })
+                }
+                HeadMove::Right => head += 1,
+            }
+        }
+        Ok(TwoWayResult::Loop)
+    }
+
+    pub fn evaluate(&self, string: &str) -> Result<bool, DFAEvaluationError> {
+        Ok(self.simulate(string)? == TwoWayResult::Accept)
+    }
+
+    pub fn evaluate_to_string(&self, s: &str) -> String {
+        use DFAEvaluationError::*;
+        match self.simulate(s) {
+            Ok(TwoWayResult::Accept) => String::from("true"),
+            Ok(TwoWayResult::Reject) => String::from("false"),
+            Ok(TwoWayResult::Loop) => String::from("false (loops forever)"),
+            Err(InvalidChar(c)) => format!("invalid character: {c}"),
+            Err(InvalidState(i)) => format!("invalid state: {i}"),
+            Err(NoTransition((i, c))) => format!("no transition found for character {c} and state {i}"),
+        }
+    }
+
+    // the head enters the last cell of a prefix in state `state`; follows the run, using
+    // `table` for every excursion back into the prefix, until it leaves the cell to the right.
+    // returns the state it leaves with, or None if it rejects or loops first
+    fn exit_right(&self, state: i32, symbol: TapeSymbol, table: &[Option<i32>]) -> Option<i32> {
+        let mut seen = vec![false; self.states as usize];
+        let mut state = state;
+        loop {
+            if seen[state as usize] {
+                return None;
+            }
+            seen[state as usize] = true;
+            match self.transition.get(&(state, symbol)) {
+                Some(&(to, _)) if to < 0 || to >= self.states => return None,
+                Some(&(to, HeadMove::Right)) => return Some(to),
+                Some(&(to, HeadMove::Left)) => state = table[to as usize]?,
+                None => return None,
+            }
+        }
+    }
+
+    // Shepherdson's construction: a prefix ⊢x is summarised by the state the run from the start
+    // first leaves it with, and for every state q the state the run leaves it with after
+    // entering its last cell from the right in q. those summaries are the states of the DFA
+    pub fn to_dfa(&self) -> DFA {
+        let mut result = DFA::new();
+        result.alphabet = self.alphabet.clone();
+        if self.states == 0 {
+            result.states = 1;
+            result.accepting = vec![false];
+            result.transition.extend(self.alphabet.iter().map(|&c| ((0, c), 0)));
+            return result;
+        }
+        let empty = vec![None; self.states as usize];
+        let table: Vec<Option<i32>> = (0..self.states)
+            .map(|q| self.exit_right(q, TapeSymbol::LeftEnd, &empty))
+            .collect();
+        let start = (table[0], table);
+        let mut index: HashMap<(Option<i32>, Vec<Option<i32>>), i32> = HashMap::new();
+        let mut queue = vec![start.clone()];
+        index.insert(start, 0);
+        let mut next = 0;
+        while next < queue.len() {
+            let (first, table) = queue[next].clone();
+            let accept = first
+                .and_then(|q| self.exit_right(q, TapeSymbol::RightEnd, &table))
+                .is_some_and(|q| self.accepting[q as usize]);
+            result.accepting.push(accept);
+            for &char in &self.alphabet {
+                let symbol = TapeSymbol::Symbol(char);
+                let new_table: Vec<Option<i32>> =
+                    (0..self.states).map(|q| self.exit_right(q, symbol, &table)).collect();
+                let target = (first.and_then(|q| new_table[q as usize]), new_table);
+                let id = match index.get(&target) {
+                    Some(&id) => id,
+                    None => {
+                        let id = queue.len() as i32;
+                        index.insert(target.clone(), id);
+                        queue.push(target);
+                        id
+                    }
+                };
+                result.transition.insert((next as i32, char), id);
+            }
+            next += 1;
+        }
+        result.states = queue.len() as i32;
+        result.optimize()
+    }
+}
+
+impl Display for TwoWayDFA {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
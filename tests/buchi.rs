@@ -0,0 +1,135 @@
+use std::{env, fs, process::Command};
+
+use automata::{buchi::Buchi, dfa::DFA};
+
+// infinitely many a's
+const INFINITELY_MANY_A: &str = "2\n1\na,b\n1,0\n1,0\n";
+
+// finitely many a's, guessing when the last one has been read
+const FINITELY_MANY_A: &str = "2 %states\n1 %accepting states\na,b %alphabet\n0,0 1\n-,1\n";
+
+fn open(s: &str) -> Buchi {
+    Buchi::from_string(String::from(s))
+}
+
+// (u, v, whether u·v^ω has infinitely many a's)
+const WORDS: [(&str, &str, bool); 7] = [
+    ("", "a", true),
+    ("", "b", false),
+    ("aaa", "b", false),
+    ("b", "ab", true),
+    ("", "bba", true),
+    ("abab", "bb", false),
+    ("ba", "aab", true),
+];
+
+#[test]
+fn accepts_lasso() {
+    let infinite = open(INFINITELY_MANY_A);
+    let finite = open(FINITELY_MANY_A);
+    for (u, v, expected) in WORDS {
+        assert_eq!(infinite.accepts_lasso(u, v).unwrap(), expected, "{u}({v})");
+        assert_eq!(finite.accepts_lasso(u, v).unwrap(), !expected, "{u}({v})");
+    }
+    // a finite word is not in any language of infinite words
+    assert!(!infinite.accepts_lasso("a", "").unwrap());
+    assert!(infinite.accepts_lasso("a", "c").is_err());
+}
+
+#[test]
+fn lasso_accepts_every_way_of_writing_its_word() {
+    let lasso = Buchi::lasso("a", "ba");
+    assert!(lasso.accepts_lasso("ab", "ab").unwrap());
+    assert!(lasso.accepts_lasso("", "ab").unwrap());
+    assert!(lasso.accepts_lasso("aba", "baba").unwrap());
+    assert!(!lasso.accepts_lasso("", "ba").unwrap());
+    assert!(!lasso.accepts_lasso("a", "b").unwrap());
+    assert_eq!(lasso.states, 3);
+}
+
+#[test]
+fn intersection_and_union() {
+    let infinite = open(INFINITELY_MANY_A);
+    let finite = open(FINITELY_MANY_A);
+    assert!(infinite.intersect(&finite).is_empty());
+    assert!(!infinite.union(&finite).is_empty());
+
+    // infinitely many a's and infinitely many b's
+    let infinitely_many_b = open("2\n1\nb,a\n1,0\n1,0\n");
+    let both = infinite.intersect(&infinitely_many_b);
+    let either = infinite.union(&infinitely_many_b);
+    for (u, v, _) in WORDS {
+        let a = v.contains('a');
+        let b = v.contains('b');
+        assert_eq!(both.accepts_lasso(u, v).unwrap(), a && b, "{u}({v})");
+        assert_eq!(either.accepts_lasso(u, v).unwrap(), a || b, "{u}({v})");
+    }
+    for (u, v, _) in WORDS {
+        assert!(infinite.union(&finite).accepts_lasso(u, v).unwrap(), "{u}({v})");
+    }
+}
+
+#[test]
+fn accepting_lasso_is_accepted() {
+    for s in [INFINITELY_MANY_A, FINITELY_MANY_A] {
+        let buchi = open(s);
+        let (u, v) = buchi.accepting_lasso().unwrap();
+        assert!(!v.is_empty());
+        assert!(buchi.accepts_lasso(&u, &v).unwrap(), "{u}({v})");
+    }
+    // the accepting state can be reached but not revisited
+    let buchi = open("3\n1\na\n1\n2\n2\n");
+    assert_eq!(buchi.accepting_lasso(), None);
+    assert!(Buchi::new().is_empty());
+}
+
+#[test]
+fn dfas_accept_words_that_visit_accepting_states_forever() {
+    let dfa = DFA::open_dfa_file("begins_with_ab.dfa").unwrap();
+    let buchi = Buchi::from_dfa(&dfa);
+    assert!(buchi.accepts_lasso("ab", "a").unwrap());
+    assert!(buchi.accepts_lasso("", "ab").unwrap());
+    assert!(!buchi.accepts_lasso("", "ba").unwrap());
+    assert!(!buchi.accepts_lasso("b", "a").unwrap());
+    let buchi = Buchi::from_dfa(&dfa.with_start(1).unwrap());
+    assert!(buchi.accepts_lasso("b", "a").unwrap());
+    assert!(!buchi.accepts_lasso("a", "b").unwrap());
+}
+
+#[test]
+fn to_string_round_trip() {
+    let finite = open(FINITELY_MANY_A);
+    let text = finite.to_string();
+    assert!(text.contains("\n0,0 1 %0:\n"));
+    let read = open(&text);
+    assert_eq!(read.states, finite.states);
+    assert_eq!(read.accepting, finite.accepting);
+    assert_eq!(read.alphabet, finite.alphabet);
+    assert_eq!(read.transition, finite.transition);
+}
+
+#[test]
+fn evaluate_infinite_words() {
+    let infinite = open(INFINITELY_MANY_A);
+    assert_eq!(infinite.evaluate_to_string("b(ab)"), "true");
+    assert_eq!(infinite.evaluate_to_string("aa(b)"), "false");
+    assert_eq!(infinite.evaluate_to_string("(c)"), "invalid character: c");
+    assert_eq!(
+        infinite.evaluate_to_string("ab"),
+        "infinite words are written as u(v), meaning u followed by v forever"
+    );
+
+    let dir = env::temp_dir().join(format!("automata_buchi_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("finitely_many_a.buchi");
+    fs::write(&path, FINITELY_MANY_A).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .arg("evaluate")
+        .arg(&path)
+        .args(["aa(b)", "(ab)"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "aa(b): true\n(ab): false\n");
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use std::{env, fs, process::Command};
+
+use automata::{
+    cfg::{Grammar, GrammarError, Symbol::*},
+    dfa::DFA,
+    dfa_gen,
+    pda::PDA,
+};
+use common::strings;
+
+// quoted terminals that clash with the separators, comments and arrows of the format
+const AWKWARD: &str = "S -> '|' S '%' | ' ' A | \"::=\" | ε % a comment with a ' in it
+A -> 'a' '\\'' '\\\\' | '-' '>' S % another\n";
+
+// to_string groups the rules by nonterminal, so only the order may differ
+fn assert_same_grammar(lhs: &Grammar, rhs: &Grammar) {
+    assert_eq!(lhs.start, rhs.start);
+    let mut lhs = lhs.rules.clone();
+    let mut rhs = rhs.rules.clone();
+    lhs.sort();
+    rhs.sort();
+    assert_eq!(lhs, rhs);
+}
+
+#[test]
+fn quoted_separators_are_terminals() {
+    let grammar = Grammar::from_string(AWKWARD).unwrap();
+    assert_eq!(grammar.nonterminals(), vec!["S", "A"]);
+    let mut terminals = grammar.terminals();
+    terminals.sort();
+    assert_eq!(terminals, vec![' ', '%', '\'', '-', ':', '=', '>', '\\', 'a', '|']);
+    for s in ["", "::=", "|::=%", "||%%", " a'\\", " ->::=", "| ->%"] {
+        assert!(grammar.earley(s), "{s:?}");
+    }
+    for s in ["|", "%", "a'\\", " a'"] {
+        assert!(!grammar.earley(s), "{s:?}");
+    }
+}
+
+#[test]
+fn to_string_round_trip() {
+    let grammar = Grammar::from_string(AWKWARD).unwrap();
+    assert_same_grammar(&Grammar::from_string(&grammar.to_string()).unwrap(), &grammar);
+    // the helper nonterminals of the normal form are named after awkward terminals too
+    let cnf = grammar.to_cnf();
+    assert_same_grammar(&Grammar::from_string(&cnf.to_string()).unwrap(), &cnf);
+    let read = Grammar::from_string(&cnf.to_string()).unwrap();
+    for s in strings(&['|', '%', ' ', 'a'], 4) {
+        assert_eq!(read.cyk(&s), grammar.earley(&s), "{s:?}");
+    }
+}
+
+fn balanced(s: &str) -> bool {
+    let mut depth = 0;
+    for c in s.chars() {
+        depth += if c == '(' { 1 } else { -1 };
+        if depth < 0 {
+            return false;
+        }
+    }
+    depth == 0
+}
+
+// a^n b^n, the standard language that no dfa accepts
+fn anbn() -> Grammar {
+    Grammar::from_string("<S> ::= a <S> b | ε\n").unwrap()
+}
+
+#[test]
+fn parse_errors() {
+    assert!(matches!(
+        Grammar::from_string("% nothing\n\n"),
+        Err(GrammarError::NoRules)
+    ));
+    assert!(matches!(
+        Grammar::from_string("| a\n"),
+        Err(GrammarError::InvalidLine(1))
+    ));
+    assert!(matches!(
+        Grammar::from_string("S -> a\nS a\n"),
+        Err(GrammarError::InvalidLine(2))
+    ));
+    assert!(matches!(
+        Grammar::from_string("S -> 'a\n"),
+        Err(GrammarError::InvalidLine(1))
+    ));
+    assert!(matches!(
+        Grammar::from_string("S T -> a\n"),
+        Err(GrammarError::InvalidLine(1))
+    ));
+    assert!(matches!(
+        Grammar::open_grammar_file("no_such_grammar"),
+        Err(GrammarError::UnableToOpen(_))
+    ));
+}
+
+#[test]
+fn continuation_lines_and_runs_of_terminals() {
+    let grammar = Grammar::from_string("S -> ab S\n  | c % end\nT -> eps\n").unwrap();
+    assert_eq!(grammar.rules.len(), 3);
+    assert_eq!(
+        grammar.rules[0].1,
+        vec![Terminal('a'), Terminal('b'), Nonterminal(String::from("S"))]
+    );
+    assert!(grammar.rules[2].1.is_empty());
+    assert!(grammar.earley("ababc"));
+    assert!(!grammar.earley("aba"));
+}
+
+#[test]
+fn cnf_has_only_binary_and_terminal_rules() {
+    let grammar = Grammar::open_grammar_file("balanced_parentheses").unwrap();
+    for g in [grammar.clone(), anbn(), Grammar::from_string(AWKWARD).unwrap()] {
+        let cnf = g.to_cnf();
+        for (lhs, rhs) in &cnf.rules {
+            match rhs.as_slice() {
+                [] => assert_eq!(*lhs, cnf.start),
+                [Terminal(_)] => {}
+                [Nonterminal(b), Nonterminal(c)] => assert!(*b != cnf.start && *c != cnf.start),
+                _ => panic!("{lhs} -> {rhs:?}"),
+            }
+        }
+    }
+}
+
+#[test]
+fn cyk_and_earley_agree() {
+    let grammar = Grammar::open_grammar_file("balanced_parentheses.cfg").unwrap();
+    for s in strings(&['(', ')'], 8) {
+        assert_eq!(grammar.earley(&s), balanced(&s), "{s:?}");
+        assert_eq!(grammar.cyk(&s), balanced(&s), "{s:?}");
+    }
+    let grammar = anbn();
+    for s in strings(&['a', 'b'], 6) {
+        let n = s.len() / 2;
+        let expected = s == "a".repeat(n) + &"b".repeat(n);
+        assert_eq!(grammar.earley(&s), expected, "{s:?}");
+        assert_eq!(grammar.cyk(&s), expected, "{s:?}");
+    }
+}
+
+#[test]
+fn emptiness_and_useless_rules() {
+    let grammar = Grammar::from_string("S -> a | A B\nA -> a A\nB -> b\nC -> c\n").unwrap();
+    assert!(!grammar.is_empty());
+    assert_eq!(grammar.witness().as_deref(), Some("a"));
+    let useful = grammar.remove_useless();
+    assert_eq!(useful.rules, vec![(String::from("S"), vec![Terminal('a')])]);
+    let empty = Grammar::from_string("S -> a S\n").unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.witness(), None);
+    assert!(empty.remove_useless().rules.is_empty());
+}
+
+#[test]
+fn intersection_with_a_dfa() {
+    // balanced strings that start with ab are impossible, but those starting with (( are not
+    let grammar = Grammar::open_grammar_file("balanced_parentheses.cfg").unwrap();
+    let ab = DFA::open_dfa_file("begins_with_ab.dfa").unwrap();
+    assert!(grammar.intersect_dfa(&ab).is_empty());
+    let nested = dfa_gen::begins_with(&['(', ')'], "((").unwrap();
+    let intersection = grammar.intersect_dfa(&nested);
+    assert_eq!(intersection.witness().as_deref(), Some("(())"));
+    for s in strings(&['(', ')'], 6) {
+        let expected = balanced(&s) && s.starts_with("((");
+        assert_eq!(intersection.earley(&s), expected, "{s:?}");
+    }
+    let read = Grammar::from_string(&intersection.to_string()).unwrap();
+    assert_same_grammar(&read, &intersection);
+
+    // a^n b^n with an even n is a^n b^n intersected with (aa)*(bb)*
+    let even = dfa_gen::modulo_n(&['a', 'b'], &['a', 'b'], 0, 4).unwrap();
+    let intersection = anbn().intersect_dfa(&even);
+    for s in strings(&['a', 'b'], 8) {
+        let n = s.len() / 2;
+        let expected = s == "a".repeat(n) + &"b".repeat(n) && n % 2 == 0;
+        assert_eq!(intersection.earley(&s), expected, "{s:?}");
+    }
+}
+
+#[test]
+fn pushdown_automaton_conversions() {
+    let grammar = Grammar::open_grammar_file("balanced_parentheses.cfg").unwrap();
+    let pda = grammar.to_pda();
+    for s in strings(&['(', ')'], 6) {
+        assert_eq!(pda.evaluate(&s).unwrap(), balanced(&s), "{s:?}");
+    }
+    let pda = PDA::open_pda_file("balanced_parentheses.pda").unwrap();
+    let from_pda = Grammar::from_pda(&pda);
+    for s in strings(&['(', ')'], 6) {
+        assert_eq!(from_pda.earley(&s), balanced(&s), "{s:?}");
+    }
+    let read = Grammar::from_string(&from_pda.to_string()).unwrap();
+    assert_same_grammar(&read, &from_pda);
+}
+
+#[test]
+fn cfg_intersect_command() {
+    let dir = env::temp_dir().join(format!("automata_cfg_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("intersection.cfg");
+    let run = |dfa: &str| {
+        Command::new(env!("CARGO_BIN_EXE_dfa"))
+            .args(["cfg-intersect", "balanced_parentheses.cfg", dfa])
+            .arg(&output)
+            .output()
+            .unwrap()
+    };
+    let nested = dir.join("nested.dfa");
+    dfa_gen::begins_with(&['(', ')'], "((")
+        .unwrap()
+        .save_dfa_file(nested.to_str().unwrap())
+        .unwrap();
+    let result = run(nested.to_str().unwrap());
+    assert!(result.status.success());
+    assert_eq!(
+        String::from_utf8(result.stdout).unwrap(),
+        "nonempty, for example: (())\n"
+    );
+    let written = Grammar::open_grammar_file(output.to_str().unwrap()).unwrap();
+    assert!(written.earley("(()())"));
+    assert!(!written.earley("()()"));
+
+    let result = run("begins_with_ab.dfa");
+    assert_eq!(String::from_utf8(result.stdout).unwrap(), "empty\n");
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,323 @@
+mod common;
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use automata::{
+    codegen,
+    dfa::{DFAEvaluationError, DFA},
+    dfa_gen,
+};
+
+const MAX_LENGTH: usize = 4;
+
+// the automata every backend is checked against, with the name of the generated function
+fn dfas() -> Vec<(&'static str, DFA)> {
+    vec![
+        ("begins_with_ab", DFA::open_dfa_file("begins_with_ab.dfa").unwrap()),
+        ("ends_with_aba", dfa_gen::ends_wtih(&['a', 'b'], "aba").unwrap()),
+        (
+            "mod_three_from_one",
+            dfa_gen::modulo_n(&['a', 'b', 'c'], &['a'], 0, 3)
+                .unwrap()
+                .with_start(1)
+                .unwrap(),
+        ),
+        (
+            "odd_escapes",
+            dfa_gen::modulo_n(&['\'', '"', '\\', 'é'], &['\\', 'é'], 1, 2).unwrap(),
+        ),
+        ("only_xy", dfa_gen::only_string(&['x', 'y'], "xy").unwrap().trim()),
+        ("no_alphabet", dfa_gen::all_strings(&[])),
+    ]
+}
+
+// every string up to MAX_LENGTH over the alphabet plus one character outside it
+fn strings(dfa: &DFA) -> Vec<String> {
+    let invalid = ['z', '!', '€'].into_iter().find(|c| !dfa.alphabet.contains(c)).unwrap();
+    let mut chars = dfa.alphabet.clone();
+    chars.push(invalid);
+    common::strings(&chars, MAX_LENGTH)
+}
+
+// one line per string in the form every generated test program prints:
+// `true`, `false`, or `invalid <code point>`
+fn expected(dfa: &DFA, strings: &[String]) -> Vec<String> {
+    strings
+        .iter()
+        .map(|s| match dfa.evaluate(s) {
+            Ok(a) => a.to_string(),
+            Err(DFAEvaluationError::InvalidChar(c)) => format!("invalid {}", c as u32),
+            Err(e) => panic!("{s:?}: {e:?}"),
+        })
+        .collect()
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("automata_codegen_{name}_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run(command: &mut Command) -> Vec<String> {
+    let output = command.output().unwrap();
+    assert!(
+        output.status.success(),
+        "{command:?} failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+// false, with a note, if a compiler or interpreter a test needs is not installed
+fn available(tool: &str) -> bool {
+    let found = Command::new(tool)
+        .arg("--version")
+        .output()
+        .is_ok_and(|o| o.status.success());
+    if !found {
+        eprintln!("{tool} not found, skipping");
+    }
+    found
+}
+
+// runs `dfa codegen` with an output file name that has to be turned into the function `name`
+fn codegen(dir: &Path, language: &str, extension: &str, name: &str, dfa: &DFA) -> PathBuf {
+    let input = dir.join(format!("{name}.dfa"));
+    dfa.save_dfa_file(input.to_str().unwrap()).unwrap();
+    let output = dir.join(format!("{}.{extension}", name.replace('_', "-")));
+    run(Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["codegen", language])
+        .arg(&input)
+        .arg(&output));
+    output
+}
+
+// the generated file, function name and strings for every automaton, for the script drivers
+fn script_cases(dir: &Path, language: &str, extension: &str) -> (String, Vec<String>) {
+    let mut cases = vec![];
+    let mut lines = vec![];
+    for (name, dfa) in dfas() {
+        let file = codegen(dir, language, extension, name, &dfa);
+        let strings = strings(&dfa);
+        lines.extend(expected(&dfa, &strings));
+        cases.push((file.to_str().unwrap().to_string(), name, strings));
+    }
+    (serde_json::to_string(&cases).unwrap(), lines)
+}
+
+#[test]
+fn rust_backend_agrees_with_evaluate() {
+    let dir = scratch_dir("rust");
+    let mut program = String::new();
+    let mut main = String::from("fn main() {\n");
+    let mut lines = vec![];
+    for (name, dfa) in dfas() {
+        program.push_str(&dfa.to_rust(name));
+        program.push('\n');
+        let strings = strings(&dfa);
+        main.push_str(&format!("    for s in {strings:?} {{\n"));
+        main.push_str(&format!("        match {name}_evaluate(s) {{\n"));
+        main.push_str("            Ok(a) => println!(\"{a}\"),\n");
+        main.push_str("            Err(c) => println!(\"invalid {}\", c as u32),\n");
+        main.push_str("        }\n    }\n");
+        lines.extend(expected(&dfa, &strings));
+    }
+    main.push_str("}\n");
+    program.push_str(&main);
+    let source = dir.join("main.rs");
+    let binary = dir.join("main");
+    fs::write(&source, program).unwrap();
+    let rustc = env::var("RUSTC").unwrap_or(String::from("rustc"));
+    run(Command::new(rustc)
+        .args(["--edition", "2021", "-A", "warnings", "-o"])
+        .arg(&binary)
+        .arg(&source));
+    assert_eq!(run(&mut Command::new(&binary)), lines);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn c_backend_agrees_with_evaluate() {
+    if !available("cc") {
+        return;
+    }
+    let dir = scratch_dir("c");
+    let mut sources = vec![];
+    let mut program = String::from("#include <stdio.h>\n");
+    let mut main = String::from("int main(void) {\n    uint32_t c = 0;\n    int result;\n");
+    let mut lines = vec![];
+    for (name, dfa) in dfas() {
+        sources.push(codegen(&dir, "c", "c", name, &dfa));
+        program.push_str(&format!("#include \"{name}.h\"\n"));
+        let strings = strings(&dfa);
+        for s in &strings {
+            let literal: String = s.bytes().map(|b| format!("\\{b:03o}")).collect();
+            main.push_str(&format!(
+                "    result = (int){name}_evaluate(\"{literal}\", {}, &c);\n    report(result, c);\n",
+                s.len()
+            ));
+        }
+        lines.extend(expected(&dfa, &strings));
+    }
+    program.push_str(
+        "
+static void report(int result, uint32_t c) {
+    if (result < 0) {
+        printf(\"invalid %u\\n\", (unsigned)c);
+    } else {
+        printf(result ? \"true\\n\" : \"false\\n\");
+    }
+}
+
+",
+    );
+    program.push_str(&main);
+    program.push_str("    return 0;\n}\n");
+    let source = dir.join("main.c");
+    let binary = dir.join("main");
+    fs::write(&source, program).unwrap();
+    run(Command::new("cc")
+        .args(["-std=c99", "-Wall", "-Werror", "-o"])
+        .arg(&binary)
+        .arg(&source)
+        .args(&sources));
+    assert_eq!(run(&mut Command::new(&binary)), lines);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn python_backend_agrees_with_evaluate() {
+    if !available("python3") {
+        return;
+    }
+    let dir = scratch_dir("python");
+    let (cases, lines) = script_cases(&dir, "python", "py");
+    let driver = "
+import importlib.util, json, sys
+
+for file, name, strings in json.loads(sys.argv[1]):
+    spec = importlib.util.spec_from_file_location(name, file)
+    module = importlib.util.module_from_spec(spec)
+    spec.loader.exec_module(module)
+    for s in strings:
+        try:
+            print(\"true\" if getattr(module, name)(s) else \"false\")
+        except module.InvalidChar as e:
+            print(f\"invalid {ord(e.char)}\")
+";
+    assert_eq!(run(Command::new("python3").args(["-c", driver, &cases])), lines);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn javascript_backend_agrees_with_evaluate() {
+    if !available("node") {
+        return;
+    }
+    let dir = scratch_dir("javascript");
+    let (cases, lines) = script_cases(&dir, "javascript", "js");
+    let driver = "
+for (const [file, name, strings] of JSON.parse(process.argv[1])) {
+  const matcher = require(file)[name];
+  for (const s of strings) {
+    try {
+      console.log(matcher(s) ? \"true\" : \"false\");
+    } catch (error) {
+      console.log(\"invalid \" + error.invalidChar.codePointAt(0));
+    }
+  }
+}
+";
+    assert_eq!(run(Command::new("node").args(["-e", driver, &cases])), lines);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn reserved_words_get_a_suffix() {
+    let rust = codegen::backend("rust").unwrap();
+    assert_eq!(rust.function_name("fn"), "fn_");
+    assert_eq!(rust.function_name("Self"), "Self_");
+    assert_eq!(rust.function_name("class"), "class");
+    assert_eq!(rust.function_name("my-dfa.v2"), "my_dfa_v2");
+    assert_eq!(rust.function_name("2dfa"), "matches");
+    assert_eq!(rust.function_name(""), "matches");
+    assert_eq!(codegen::backend("py").unwrap().function_name("class"), "class_");
+    assert_eq!(codegen::backend("js").unwrap().function_name("function"), "function_");
+    // the helpers next to the generated function are reserved too
+    assert_eq!(codegen::backend("c").unwrap().function_name("decode"), "decode_");
+
+    let dir = scratch_dir("reserved");
+    let dfa = DFA::open_dfa_file("begins_with_ab.dfa").unwrap();
+    let strings = ["ab", "ba"];
+    let expected = vec!["true", "false"];
+
+    let source = codegen(&dir, "rust", "rs", "fn", &dfa);
+    let main = dir.join("main.rs");
+    fs::write(
+        &main,
+        format!("include!({source:?});\n\nfn main() {{\n    for s in {strings:?} {{\n        println!(\"{{}}\", fn_(s));\n    }}\n}}\n"),
+    )
+    .unwrap();
+    let rustc = env::var("RUSTC").unwrap_or(String::from("rustc"));
+    let binary = dir.join("main");
+    run(Command::new(rustc)
+        .args(["--edition", "2021", "-A", "warnings", "-o"])
+        .arg(&binary)
+        .arg(&main));
+    assert_eq!(run(&mut Command::new(&binary)), expected);
+
+    if available("cc") {
+        let source = codegen(&dir, "c", "c", "int", &dfa);
+        assert!(dir.join("int_.h").exists());
+        let main = dir.join("main.c");
+        fs::write(
+            &main,
+            "#include <stdio.h>\n#include \"int_.h\"\n\nint main(void) {\n    printf(\"%s\\n%s\\n\", int_(\"ab\", 2) ? \"true\" : \"false\", int_(\"ba\", 2) ? \"true\" : \"false\");\n    return 0;\n}\n",
+        )
+        .unwrap();
+        run(Command::new("cc")
+            .args(["-std=c99", "-Wall", "-Werror", "-o"])
+            .arg(&binary)
+            .arg(&main)
+            .arg(&source));
+        assert_eq!(run(&mut Command::new(&binary)), expected);
+    }
+
+    if available("python3") {
+        let source = codegen(&dir, "python", "py", "class", &dfa);
+        let driver = "
+import importlib.util, sys
+
+spec = importlib.util.spec_from_file_location(\"generated\", sys.argv[1])
+module = importlib.util.module_from_spec(spec)
+spec.loader.exec_module(module)
+for s in sys.argv[2:]:
+    print(\"true\" if module.class_(s) else \"false\")
+";
+        let mut command = Command::new("python3");
+        command.args(["-c", driver]).arg(&source).args(strings);
+        assert_eq!(run(&mut command), expected);
+    }
+
+    if available("node") {
+        let source = codegen(&dir, "javascript", "js", "function", &dfa);
+        let driver = "
+const matcher = require(process.argv[1]).function_;
+for (const s of process.argv.slice(2)) {
+  console.log(matcher(s) ? \"true\" : \"false\");
+}
+";
+        let mut command = Command::new("node");
+        command.args(["-e", driver]).arg(&source).args(strings);
+        assert_eq!(run(&mut command), expected);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
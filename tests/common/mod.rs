@@ -0,0 +1,26 @@
+// helpers shared by the integration tests, not every test file uses all of them
+#![allow(dead_code)]
+
+use automata::dfa::DFA;
+
+// every string over `alphabet` with at most `length` characters, shortest first
+pub fn strings(alphabet: &[char], length: usize) -> Vec<String> {
+    let mut result = vec![String::new()];
+    let mut last = vec![String::new()];
+    for _ in 0..length {
+        last = last
+            .iter()
+            .flat_map(|s| alphabet.iter().map(move |c| format!("{s}{c}")))
+            .collect();
+        result.extend(last.iter().cloned());
+    }
+    result
+}
+
+// same alphabet and the same answer for every string up to `length`
+pub fn assert_same_language(lhs: &DFA, rhs: &DFA, length: usize) {
+    assert_eq!(lhs.alphabet, rhs.alphabet);
+    for s in strings(&lhs.alphabet, length) {
+        assert_eq!(lhs.evaluate(&s).ok(), rhs.evaluate(&s).ok(), "{s:?}");
+    }
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use std::{env, fs, process::Command};
+
+use automata::{
+    dfa::{DFAEvaluationError, DFAFileError, DFA},
+    dfa_binary::{BinaryError, DfaView},
+    dfa_gen,
+};
+use common::{assert_same_language, strings};
+
+fn begins_with_ab() -> DFA {
+    DFA::open_dfa_file("begins_with_ab.dfa").unwrap()
+}
+
+// writes the little endian `value` at `offset` and fixes up the checksum, so that only the
+// edited field is wrong
+fn edit(bytes: &[u8], offset: usize, value: u32) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    let end = bytes.len() - 4;
+    let mut hash: u32 = 0x811c9dc5;
+    for &byte in &bytes[..end] {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    bytes[end..].copy_from_slice(&hash.to_le_bytes());
+    bytes
+}
+
+fn error(bytes: &[u8]) -> BinaryError {
+    DfaView::new(bytes).unwrap_err()
+}
+
+#[test]
+fn layout() {
+    let bytes = begins_with_ab().to_binary();
+    // header, two characters, four rows of two targets, one word of accepting bits, checksum
+    assert_eq!(bytes.len(), 16 + 2 * 4 + 8 * 4 + 4 + 4);
+    assert_eq!(&bytes[..16], b"DFA\0\x01\0\0\0\x04\0\0\0\x02\0\0\0");
+    assert_eq!(&bytes[16..24], b"a\0\0\0b\0\0\0");
+    assert_eq!(&bytes[56..60], &[0b100, 0, 0, 0]);
+}
+
+#[test]
+fn view_evaluates_without_copying() {
+    let dfa = begins_with_ab();
+    let bytes = dfa.to_binary();
+    let view = DfaView::new(&bytes).unwrap();
+    assert_eq!(view.states(), 4);
+    assert_eq!(view.alphabet().collect::<Vec<_>>(), vec!['a', 'b']);
+    assert!(view.is_accepting(2) && !view.is_accepting(3));
+    assert_eq!(view.next(0, 'a').unwrap(), 1);
+    for s in strings(&['a', 'b'], 5) {
+        assert_eq!(view.evaluate(&s).unwrap(), dfa.evaluate(&s).unwrap(), "{s:?}");
+    }
+    assert!(matches!(
+        view.evaluate("abc"),
+        Err(DFAEvaluationError::InvalidChar('c'))
+    ));
+    assert_eq!(view.to_dfa(), dfa);
+
+    // the columns are looked up by character, whatever order the alphabet is in
+    let reversed = dfa_gen::begins_with(&['b', 'a'], "ab").unwrap();
+    let bytes = reversed.to_binary();
+    let view = DfaView::new(&bytes).unwrap();
+    assert_eq!(view.alphabet().collect::<Vec<_>>(), vec!['b', 'a']);
+    assert!(view.evaluate("abba").unwrap());
+    assert!(!view.evaluate("ba").unwrap());
+
+    let empty = DFA::new().to_binary();
+    assert!(matches!(
+        DfaView::new(&empty).unwrap().evaluate(""),
+        Err(DFAEvaluationError::InvalidState(0))
+    ));
+}
+
+#[test]
+fn start_state_and_partial_automata() {
+    let moved = begins_with_ab().with_start(1).unwrap();
+    let read = DFA::read_binary(&mut moved.to_binary().as_slice()).unwrap();
+    assert_eq!(read.start, 0);
+    assert_same_language(&read, &moved, 5);
+
+    let partial = begins_with_ab().trim();
+    assert!(partial.partial);
+    let read = DFA::read_binary(&mut partial.to_binary().as_slice()).unwrap();
+    assert!(!read.partial);
+    assert_eq!(read, partial.complete());
+
+    let mut written = vec![];
+    begins_with_ab().write_binary(&mut written).unwrap();
+    assert_eq!(written, begins_with_ab().to_binary());
+}
+
+#[test]
+fn invalid_files() {
+    let bytes = begins_with_ab().to_binary();
+    assert!(matches!(error(b"4\n1\n"), BinaryError::BadMagic));
+    assert!(matches!(error(b"DFA\0\x01"), BinaryError::Truncated));
+    assert!(matches!(error(&bytes[..bytes.len() - 1]), BinaryError::Truncated));
+    assert!(matches!(error(&edit(&bytes, 8, u32::MAX)), BinaryError::Truncated));
+    assert!(matches!(error(&edit(&bytes, 4, 2)), BinaryError::UnsupportedVersion(2)));
+    assert!(matches!(
+        error(&edit(&bytes, 16, 0xD800)),
+        BinaryError::InvalidChar(0xD800)
+    ));
+    assert!(matches!(error(&edit(&bytes, 24, 4)), BinaryError::InvalidState(4)));
+    assert_eq!(error(&edit(&bytes, 24, 4)).to_string(), "invalid state: 4");
+
+    let mut flipped = bytes.clone();
+    flipped[30] ^= 1;
+    assert!(matches!(error(&flipped), BinaryError::ChecksumMismatch));
+
+    // a missing transition is allowed, and only fails when it is taken
+    let missing = edit(&bytes, 24, u32::MAX);
+    let view = DfaView::new(&missing).unwrap();
+    assert!(matches!(
+        view.evaluate("ab"),
+        Err(DFAEvaluationError::NoTransition((0, 'a')))
+    ));
+    assert!(!view.evaluate("b").unwrap());
+}
+
+#[test]
+fn bin_files_are_read_and_written_by_extension() {
+    let dir = env::temp_dir().join(format!("automata_binary_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("begins_with_ab.bin");
+    let path = path.to_str().unwrap();
+    begins_with_ab().save_dfa_file(path).unwrap();
+    assert_eq!(fs::read(path).unwrap(), begins_with_ab().to_binary());
+    assert_eq!(DFA::open_dfa_file(path).unwrap(), begins_with_ab());
+
+    // the file is recognized by its contents, not its name
+    let renamed = dir.join("renamed.dfa");
+    fs::copy(path, &renamed).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .arg("evaluate")
+        .arg(&renamed)
+        .args(["abab", "b"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "abab: true\nb: false\n");
+
+    let mut flipped = fs::read(path).unwrap();
+    flipped[30] ^= 1;
+    fs::write(path, flipped).unwrap();
+    assert!(matches!(
+        DFA::open_dfa_file(path),
+        Err(DFAFileError::Binary(BinaryError::ChecksumMismatch))
+    ));
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["info", path])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("unable to open file {path}: binary dfa checksum does not match\n")
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,98 @@
+use std::{env, fs, process::Command};
+
+use automata::{dfa::DFA, dfa_gen};
+
+const BEGINS_WITH_AB: &str = "digraph dfa {
+    rankdir=LR;
+    node [shape=circle];
+    start [shape=none, label=\"\", width=0, height=0];
+    0 [shape=circle];
+    1 [shape=circle];
+    2 [shape=doublecircle];
+    3 [shape=circle];
+    start -> 0;
+    0 -> 1 [label=\"a\"];
+    0 -> 3 [label=\"b\"];
+    1 -> 3 [label=\"a\"];
+    1 -> 2 [label=\"b\"];
+    2 -> 2 [label=\"a,b\"];
+    3 -> 3 [label=\"a,b\"];
+}
+";
+
+fn begins_with_ab() -> DFA {
+    DFA::open_dfa_file("begins_with_ab.dfa").unwrap()
+}
+
+#[test]
+fn parallel_transitions_share_an_edge() {
+    assert_eq!(begins_with_ab().to_dot(false), BEGINS_WITH_AB);
+}
+
+#[test]
+fn hide_dead_leaves_out_dead_states_and_their_edges() {
+    let dot = begins_with_ab().to_dot(true);
+    assert!(!dot.contains("    3 ["));
+    assert!(!dot.contains("-> 3"));
+    assert!(dot.contains("    1 -> 2 [label=\"b\"];\n"));
+    assert!(dot.contains("    2 -> 2 [label=\"a,b\"];\n"));
+
+    // a dead start state is still drawn
+    let dot = begins_with_ab().with_start(3).unwrap().to_dot(true);
+    assert!(dot.contains("    start -> 3;\n"));
+    assert!(dot.contains("    3 -> 3 [label=\"a,b\"];\n"));
+    assert!(dot.contains("    0 -> 3 [label=\"b\"];\n"));
+}
+
+#[test]
+fn labels_are_escaped() {
+    let dfa = dfa_gen::modulo_n(&['"', '\\', 'x'], &['"'], 0, 2).unwrap();
+    let dot = dfa.to_dot(false);
+    assert!(dot.contains("    0 -> 1 [label=\"\\\"\"];\n"), "{dot}");
+    assert!(dot.contains("    0 -> 0 [label=\"\\\\,x\"];\n"), "{dot}");
+}
+
+#[test]
+fn missing_transitions_have_no_edge() {
+    let dfa = begins_with_ab().trim();
+    assert!(dfa.partial);
+    let dot = dfa.to_dot(false);
+    assert_eq!(dot.matches(" -> ").count(), 4);
+    assert!(dot.contains("    0 -> 1 [label=\"a\"];\n"));
+}
+
+#[test]
+fn dot_command() {
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_dfa"))
+            .arg("dot")
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let output = run(&["begins_with_ab.dfa"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), BEGINS_WITH_AB);
+    let output = run(&["--hide-dead", "begins_with_ab.dfa"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), begins_with_ab().to_dot(true));
+
+    let dir = env::temp_dir().join(format!("automata_dot_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("begins_with_ab.dot");
+    let output = run(&["begins_with_ab.dfa", path.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(fs::read_to_string(&path).unwrap(), BEGINS_WITH_AB);
+
+    let output = run(&["no_such_file.dfa"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with("unable to open file no_such_file.dfa: "));
+    let output = run(&["a", "b", "c"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Correct Syntax: dot [--hide-dead] <dfa> [outfilename]\n"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use std::{env, fs, process::Command};
+
+use automata::{
+    dfa::{DFAFileError, DFA},
+    dfa_gen,
+    dfa_json::DFAJsonError,
+};
+use common::assert_same_language;
+
+// begins_with_ab with named states, referred to by name and by id
+const NAMED: &str = r#"{
+  "alphabet": ["a", "b"],
+  "states": [{"id": 0, "name": "start"}, {"id": 1, "name": "seen a"}, {"id": 2}, {"id": 3, "name": "dead"}],
+  "start": "start",
+  "accepting": [2],
+  "transitions": [
+    {"from": "start", "symbol": "a", "to": "seen a"},
+    {"from": 0, "symbol": "b", "to": "dead"},
+    {"from": "seen a", "symbol": "a", "to": 3},
+    {"from": 1, "symbol": "b", "to": 2},
+    {"from": 2, "symbol": "a", "to": 2},
+    {"from": 2, "symbol": "b", "to": 2},
+    {"from": "dead", "symbol": "a", "to": "dead"},
+    {"from": "dead", "symbol": "b", "to": "dead"}
+  ]
+}"#;
+
+fn error(json: &str) -> DFAJsonError {
+    DFA::from_json(json).unwrap_err()
+}
+
+// NAMED with one piece of text replaced
+fn broken(from: &str, to: &str) -> DFAJsonError {
+    assert!(NAMED.contains(from), "{from}");
+    error(&NAMED.replacen(from, to, 1))
+}
+
+#[test]
+fn states_can_be_referred_to_by_name() {
+    let dfa = DFA::from_json(NAMED).unwrap();
+    assert_eq!(dfa.names.get(&1).map(String::as_str), Some("seen a"));
+    assert_eq!(dfa.names.get(&2), None);
+    assert!(!dfa.partial);
+    assert_same_language(&dfa, &DFA::open_dfa_file("begins_with_ab.dfa").unwrap(), 5);
+}
+
+#[test]
+fn round_trip_keeps_names_and_start() {
+    let mut dfa = DFA::from_json(NAMED).unwrap().with_start(1).unwrap();
+    dfa.names.insert(2, String::from("\"quoted\" ✓"));
+    let json = dfa.to_json();
+    assert!(json.ends_with("}\n"));
+    assert!(!json.contains("partial"));
+    let read = DFA::from_json(&json).unwrap();
+    assert_eq!(read, dfa);
+    assert_eq!(read.start, 1);
+
+    let odd = dfa_gen::modulo_n(&['"', '\\', 'é', ' '], &['"', ' '], 1, 2).unwrap();
+    assert_eq!(DFA::from_json(&odd.to_json()).unwrap(), odd);
+}
+
+#[test]
+fn invalid_files() {
+    assert!(matches!(error("{"), DFAJsonError::Syntax(_)));
+    assert!(matches!(
+        broken(r#""transitions""#, r#""extra": 1, "transitions""#),
+        DFAJsonError::Syntax(_)
+    ));
+    assert!(matches!(
+        broken(r#"["a", "b"]"#, r#"["ab", "b"]"#),
+        DFAJsonError::InvalidSymbol(s) if s == "ab"
+    ));
+    assert!(matches!(
+        broken(r#"["a", "b"]"#, r#"["a", "a"]"#),
+        DFAJsonError::DuplicateSymbol('a')
+    ));
+    assert!(matches!(
+        broken(r#"{"id": 2}"#, r#"{"id": 4}"#),
+        DFAJsonError::InvalidStateId(4)
+    ));
+    assert!(matches!(
+        broken(r#"{"id": 2}"#, r#"{"id": 1}"#),
+        DFAJsonError::DuplicateState(s) if s == "1"
+    ));
+    assert!(matches!(
+        broken(r#""name": "dead""#, r#""name": "start""#),
+        DFAJsonError::DuplicateState(s) if s == "start"
+    ));
+    assert!(matches!(
+        broken(r#""start": "start""#, r#""start": "begin""#),
+        DFAJsonError::UnknownState(s) if s == "begin"
+    ));
+    assert!(matches!(
+        broken(r#""accepting": [2]"#, r#""accepting": [-1]"#),
+        DFAJsonError::UnknownState(s) if s == "-1"
+    ));
+    assert!(matches!(
+        broken(r#""symbol": "a", "to": "seen a""#, r#""symbol": "c", "to": "seen a""#),
+        DFAJsonError::UnknownSymbol('c')
+    ));
+    assert!(matches!(
+        broken(r#""symbol": "b", "to": "dead""#, r#""symbol": "a", "to": "dead""#),
+        DFAJsonError::DuplicateTransition((0, 'a'))
+    ));
+    let missing = r#"{"from": 0, "symbol": "b", "to": "dead"},"#;
+    assert!(matches!(broken(missing, ""), DFAJsonError::MissingTransition((0, 'b'))));
+    assert_eq!(
+        broken(missing, "").to_string(),
+        "no transition found for character b and state 0"
+    );
+}
+
+#[test]
+fn partial_files_may_leave_out_transitions() {
+    let json = NAMED
+        .replacen(r#"{"from": 0, "symbol": "b", "to": "dead"},"#, "", 1)
+        .replacen(r#""transitions""#, r#""partial": true, "transitions""#, 1);
+    let dfa = DFA::from_json(&json).unwrap();
+    assert!(dfa.partial);
+    assert!(!dfa.evaluate("ba").unwrap());
+    assert!(dfa.to_json().contains("\"partial\": true"));
+    assert_eq!(DFA::from_json(&dfa.to_json()).unwrap(), dfa);
+}
+
+#[test]
+fn json_files_are_read_and_written_by_extension() {
+    let dir = env::temp_dir().join(format!("automata_json_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("named.json");
+    let path = path.to_str().unwrap();
+    let dfa = DFA::from_json(NAMED).unwrap();
+    dfa.save_dfa_file(path).unwrap();
+    assert_eq!(fs::read_to_string(path).unwrap(), dfa.to_json());
+    assert_eq!(DFA::open_dfa_file(path).unwrap(), dfa);
+
+    fs::write(path, "{}").unwrap();
+    assert!(matches!(
+        DFA::open_dfa_file(path),
+        Err(DFAFileError::Json(DFAJsonError::Syntax(_)))
+    ));
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["info", path])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with(&format!("unable to open file {path}: invalid json: ")));
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,139 @@
+use std::{env, fs, process::Command};
+
+use automata::{dfa::DFA, dfa_gen};
+
+fn begins_with_ab() -> DFA {
+    DFA::open_dfa_file("begins_with_ab.dfa").unwrap()
+}
+
+fn attribute(node: roxmltree::Node, name: &str) -> f64 {
+    node.attribute(name).unwrap().parse().unwrap()
+}
+
+// the centers of the states and the text of the labels, read back from the drawing
+fn parse(svg: &str) -> (Vec<(f64, f64)>, usize, Vec<String>) {
+    let document = roxmltree::Document::parse(svg).unwrap();
+    let circles: Vec<roxmltree::Node> = document.descendants().filter(|n| n.has_tag_name("circle")).collect();
+    let states = circles
+        .iter()
+        .filter(|n| n.attribute("fill") == Some("white"))
+        .map(|&n| (attribute(n, "cx"), attribute(n, "cy")))
+        .collect();
+    let inner = circles.len() - circles.iter().filter(|n| n.attribute("fill") == Some("white")).count();
+    let labels = document
+        .descendants()
+        .filter(|n| n.has_tag_name("text"))
+        .map(|n| n.text().unwrap_or("").to_string())
+        .collect();
+    (states, inner, labels)
+}
+
+#[test]
+fn states_are_drawn_in_breadth_first_columns() {
+    let svg = begins_with_ab().to_svg();
+    assert!(svg
+        .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"460\" height=\"270\" viewBox=\"0 0 460 270\""));
+    let (states, accepting, labels) = parse(&svg);
+    assert_eq!(states, vec![(80.0, 80.0), (230.0, 80.0), (380.0, 80.0), (230.0, 190.0)]);
+    assert_eq!(accepting, 1);
+    // the merged edge labels, then the state numbers
+    assert_eq!(labels, vec!["a", "b", "a", "b", "a,b", "a,b", "0", "1", "2", "3"]);
+    // the start arrow ends at the left of state 0
+    assert!(svg.contains("  <line x1=\"18\" y1=\"80\" x2=\"58\" y2=\"80\" stroke=\"black\""));
+}
+
+#[test]
+fn edges_in_both_directions_and_loops_are_curved() {
+    let dfa = dfa_gen::modulo_n(&['a', 'b'], &['a'], 0, 2).unwrap();
+    let svg = dfa.to_svg();
+    let paths: Vec<&str> = svg
+        .lines()
+        .filter(|l| l.contains("<path d=\"M ") && l.contains("marker-end"))
+        .collect();
+    assert_eq!(paths.len(), 4);
+    // two loops drawn as cubic curves, two bends that don't meet
+    assert_eq!(paths.iter().filter(|p| p.contains(" C ")).count(), 2);
+    let bends: Vec<&&str> = paths.iter().filter(|p| p.contains(" Q ")).collect();
+    assert_eq!(bends.len(), 2);
+    assert_ne!(bends[0].split(" Q ").nth(1), bends[1].split(" Q ").nth(1));
+
+    let straight = begins_with_ab().to_svg();
+    assert!(straight.contains("<path d=\"M 102 80 Q 155 80 208 80\""));
+}
+
+#[test]
+fn labels_are_escaped() {
+    let dfa = dfa_gen::modulo_n(&['<', '&', '"'], &['<'], 0, 2).unwrap();
+    let (_, _, labels) = parse(&dfa.to_svg());
+    assert!(labels.contains(&String::from("<")));
+    assert!(labels.contains(&String::from("&,\"")));
+}
+
+#[test]
+fn html_report() {
+    let html = begins_with_ab().to_html_report();
+    assert!(html.contains(&begins_with_ab().to_svg()));
+    assert!(html.contains("<tr><th>state</th><th>a</th><th>b</th></tr>\n"));
+    assert!(html.contains("<tr><th>&rarr;0</th><td>1</td><td>3</td></tr>\n"));
+    assert!(html.contains("<tr><th>2*</th><td>2</td><td>2</td></tr>\n"));
+    assert!(html.contains("<tr><th>dead states</th><td>1</td></tr>\n"));
+
+    // missing transitions are empty cells
+    let html = begins_with_ab().trim().to_html_report();
+    assert!(html.contains("<tr><th>&rarr;0</th><td>1</td><td></td></tr>\n"));
+}
+
+#[test]
+fn info_statistics() {
+    let info = begins_with_ab().with_start(1).unwrap().info();
+    let value = |name| info.iter().find(|(n, _)| *n == name).unwrap().1.clone();
+    assert_eq!(value("states"), "4");
+    assert_eq!(value("accepting states"), "1");
+    assert_eq!(value("alphabet size"), "2");
+    assert_eq!(value("reachable states"), "3");
+    assert_eq!(value("dead states"), "1");
+    assert_eq!(value("accepts nothing"), "false");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["info", "begins_with_ab.dfa"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "states: 4\naccepting states: 1\nalphabet size: 2\nreachable states: 4\ndead states: 1\nstates after minimization: 4\naccepts nothing: false\n"
+    );
+}
+
+#[test]
+fn svg_command() {
+    let dir = env::temp_dir().join(format!("automata_svg_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let run = |out: &str| {
+        Command::new(env!("CARGO_BIN_EXE_dfa"))
+            .args(["svg", "begins_with_ab.dfa"])
+            .arg(dir.join(out))
+            .output()
+            .unwrap()
+    };
+    assert!(run("drawing.svg").status.success());
+    assert_eq!(
+        fs::read_to_string(dir.join("drawing.svg")).unwrap(),
+        begins_with_ab().to_svg()
+    );
+    assert!(run("report.html").status.success());
+    assert_eq!(
+        fs::read_to_string(dir.join("report.html")).unwrap(),
+        begins_with_ab().to_html_report()
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["svg", "begins_with_ab.dfa"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Correct Syntax: svg <dfa> <outfilename>\n"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
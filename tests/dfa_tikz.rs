@@ -0,0 +1,91 @@
+use std::{env, fs, process::Command};
+
+use automata::{dfa::DFA, dfa_gen};
+
+const BEGINS_WITH_AB: &str = "\\begin{tikzpicture}[->, >=stealth, shorten >=1pt, auto, semithick]
+  \\node[state, initial] (q0) at (0, 0) {$q_{0}$};
+  \\node[state] (q1) at (3, 0) {$q_{1}$};
+  \\node[state] (q3) at (3, -2) {$q_{3}$};
+  \\node[state, accepting] (q2) at (6, 0) {$q_{2}$};
+  \\path
+    (q0) edge node {\\texttt{a}} (q1)
+    (q0) edge node {\\texttt{b}} (q3)
+    (q1) edge node {\\texttt{a}} (q3)
+    (q1) edge node {\\texttt{b}} (q2)
+    (q2) edge [loop above] node {\\texttt{a,b}} (q2)
+    (q3) edge [loop above] node {\\texttt{a,b}} (q3)
+  ;
+\\end{tikzpicture}
+";
+
+#[test]
+fn states_are_placed_by_distance_from_the_start() {
+    let dfa = DFA::open_dfa_file("begins_with_ab.dfa").unwrap();
+    assert_eq!(dfa.to_tikz(), BEGINS_WITH_AB);
+
+    // state 0 can't be reached from 1, so it goes in a column after the reachable ones
+    let tikz = dfa.with_start(1).unwrap().to_tikz();
+    assert!(tikz.contains("  \\node[state, initial] (q1) at (0, 0) {$q_{1}$};\n"));
+    assert!(tikz.contains("  \\node[state] (q0) at (6, 0) {$q_{0}$};\n"), "{tikz}");
+}
+
+#[test]
+fn edges_in_both_directions_are_bent() {
+    let dfa = dfa_gen::modulo_n(&['a', 'b'], &['a'], 0, 2).unwrap();
+    let tikz = dfa.to_tikz();
+    assert!(tikz.contains("    (q0) edge [bend left] node {\\texttt{a}} (q1)\n"));
+    assert!(tikz.contains("    (q1) edge [bend left] node {\\texttt{a}} (q0)\n"));
+    assert!(tikz.contains("    (q0) edge [loop above] node {\\texttt{b}} (q0)\n"));
+    assert!(tikz.contains("\\node[state, initial, accepting] (q0)"));
+}
+
+#[test]
+fn labels_are_escaped() {
+    let dfa = dfa_gen::modulo_n(&['\\', '^', '~', '#', '$', '%', '&', '_', '{', '}'], &['\\'], 0, 2).unwrap();
+    let tikz = dfa.to_tikz();
+    assert!(tikz.contains("node {\\texttt{\\textbackslash{}}} (q1)"), "{tikz}");
+    assert!(tikz.contains("node {\\texttt{\\textasciicircum{},\\textasciitilde{},\\#,\\$,\\%,\\&,\\_,\\{,\\}}} (q0)"));
+}
+
+#[test]
+fn machines_without_transitions_have_no_path() {
+    let dfa = DFA::from_string(String::from("1\n0\n\n")).unwrap();
+    assert_eq!(
+        dfa.to_tikz(),
+        "\\begin{tikzpicture}[->, >=stealth, shorten >=1pt, auto, semithick]\n  \\node[state, initial, accepting] (q0) at (0, 0) {$q_{0}$};\n\\end{tikzpicture}\n"
+    );
+}
+
+#[test]
+fn tikz_command() {
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_dfa"))
+            .arg("tikz")
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let output = run(&["begins_with_ab.dfa"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), BEGINS_WITH_AB);
+
+    let dir = env::temp_dir().join(format!("automata_tikz_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("begins_with_ab.tex");
+    let output = run(&["--standalone", "begins_with_ab.dfa", path.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        format!(
+            "\\documentclass[tikz, border=5pt]{{standalone}}\n\\usetikzlibrary{{automata, arrows}}\n\\begin{{document}}\n{BEGINS_WITH_AB}\\end{{document}}\n"
+        )
+    );
+
+    let output = run(&[]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Correct Syntax: tikz [--standalone] <dfa> [outfilename]\n"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
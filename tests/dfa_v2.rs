@@ -0,0 +1,36 @@
+mod common;
+
+use automata::{
+    dfa::DFA,
+    dfa_gen,
+    dfa_v2::{self, DFAParseError},
+};
+use common::assert_same_language;
+
+#[test]
+fn v1_to_v2_round_trip_with_awkward_symbols() {
+    let v1 = "2 %states\n1 %accepting states\n>,-,#,',\\ %alphabet\n1,0,0,1,1\n0,1,0,1,0\n";
+    let dfa = DFA::from_v1_string(v1).unwrap();
+    assert_eq!(dfa.alphabet, vec!['>', '-', '#', '\'', '\\']);
+    let v2 = dfa.to_v2_string();
+    assert!(dfa_v2::is_v2(&v2));
+    assert_same_language(&dfa, &DFA::from_v2_string(&v2).unwrap(), 3);
+}
+
+// the v1 format cannot hold commas or spaces, so these only go through v2
+#[test]
+fn v2_round_trip_with_separators_and_escapes() {
+    let alphabet = [',', ' ', '>', '-', '#', '\'', '\\', '\t', '\n', 'a'];
+    let dfa = dfa_gen::modulo_n(&alphabet, &[',', '>', ' ', '\''], 1, 3).unwrap();
+    let v2 = dfa.to_v2_string();
+    assert_same_language(&dfa, &DFA::from_v2_string(&v2).unwrap(), 3);
+}
+
+#[test]
+fn parse_errors_are_not_swallowed() {
+    let s = String::from("dfa v2\nalphabet a\nq0 -b-> q1\n");
+    assert!(matches!(
+        DFA::from_string(s),
+        Err(DFAParseError::UnknownSymbol((3, 'b')))
+    ));
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use std::{env, fs, process::Command};
+
+use automata::{dfa::DFA, dfa_gen};
+use common::strings;
+
+fn check(words: &[&str], states: i32) {
+    let dfa = dfa_gen::from_words(&['a', 'b'], words).unwrap();
+    for s in strings(&['a', 'b'], 5) {
+        assert_eq!(
+            dfa.evaluate(&s).unwrap(),
+            words.contains(&s.as_str()),
+            "{words:?} {s:?}"
+        );
+    }
+    // the minimal automaton, counting the rejecting sink
+    assert_eq!(dfa.states, states, "{words:?}");
+    assert_eq!(dfa.optimize().states, states, "{words:?}");
+}
+
+#[test]
+fn only_the_empty_word() {
+    check(&[""], 2);
+    check(&["", ""], 2);
+}
+
+#[test]
+fn no_words() {
+    let dfa = dfa_gen::from_words(&['a', 'b'], &[]).unwrap();
+    for s in strings(&['a', 'b'], 3) {
+        assert!(!dfa.evaluate(&s).unwrap(), "{s:?}");
+    }
+}
+
+#[test]
+fn words_that_are_prefixes_of_other_words() {
+    check(&["a", "ab"], 4);
+    check(&["", "a", "aa", "aaa"], 5);
+    check(&["abba", "ab", "", "abb"], 6);
+}
+
+#[test]
+fn common_suffixes_share_states() {
+    // ab, bb and aab all end in the same b, so only the prefixes need their own states
+    check(&["ab", "bb", "aab"], 5);
+    check(&["aa", "ab", "ba", "bb"], 4);
+    // after ab and after ba the rest is ab or ba either way
+    check(&["abab", "baba", "abba", "baab"], 8);
+}
+
+#[test]
+fn unsorted_and_repeated_words() {
+    let sorted = dfa_gen::from_words(&['a', 'b'], &["a", "ab", "b", "ba"]).unwrap();
+    let shuffled = dfa_gen::from_words(&['a', 'b'], &["ba", "a", "ba", "b", "ab", "a"]).unwrap();
+    assert_eq!(shuffled, sorted);
+}
+
+#[test]
+fn words_outside_the_alphabet() {
+    assert!(dfa_gen::from_words(&['a', 'b'], &["ab", "abc"]).is_err());
+    let dfa = dfa_gen::from_words(&['é', 'a', '€'], &["é€", "aé"]).unwrap();
+    assert!(dfa.evaluate("é€").unwrap());
+    assert!(!dfa.evaluate("€é").unwrap());
+}
+
+#[test]
+fn gen_words_command() {
+    let dir = env::temp_dir().join(format!("automata_words_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let words = dir.join("words.txt");
+    let output = dir.join("words.dfa");
+    // blank lines are skipped, so the empty word cannot be listed
+    fs::write(&words, "ba\r\nab\n\nabb\n").unwrap();
+    let result = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["gen", "words"])
+        .arg(&output)
+        .arg("a,b")
+        .arg(&words)
+        .output()
+        .unwrap();
+    assert!(result.status.success());
+    let dfa = DFA::open_dfa_file(output.to_str().unwrap()).unwrap();
+    let expected = dfa_gen::from_words(&['a', 'b'], &["ab", "abb", "ba"]).unwrap();
+    assert_eq!(dfa, expected);
+    assert!(!dfa.evaluate("").unwrap());
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use std::{env, fs, process::Command};
+
+use automata::{
+    dfa::{DFAFileError, DFA},
+    dfa_gen,
+    dfa_jff::JffError,
+};
+use common::assert_same_language;
+
+// begins_with_ab as JFLAP would save it, with the initial state listed last and no dead state
+const JFLAP: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?><!--Created with JFLAP 7.1.-->
+<structure>
+	<type>fa</type>
+	<automaton>
+		<!--The list of states.-->
+		<state id="7" name="q7">
+			<x>240.0</x>
+			<y>100.0</y>
+		</state>
+		<state id="9" name="q9">
+			<x>380.0</x>
+			<y>100.0</y>
+			<final/>
+		</state>
+		<state id="3" name="q3">
+			<x>100.0</x>
+			<y>100.0</y>
+			<initial/>
+		</state>
+		<!--The list of transitions.-->
+		<transition>
+			<from>3</from>
+			<to>7</to>
+			<read>a</read>
+		</transition>
+		<transition>
+			<from>7</from>
+			<to>9</to>
+			<read>b</read>
+		</transition>
+		<transition>
+			<from>9</from>
+			<to>9</to>
+			<read>a</read>
+		</transition>
+		<transition>
+			<from>9</from>
+			<to>9</to>
+			<read>b</read>
+		</transition>
+	</automaton>
+</structure>"#;
+
+fn error(from: &str, to: &str) -> JffError {
+    assert!(JFLAP.contains(from), "{from}");
+    DFA::from_jff(&JFLAP.replacen(from, to, 1)).unwrap_err()
+}
+
+#[test]
+fn jflap_files_are_renumbered_and_completed() {
+    let dfa = DFA::from_jff(JFLAP).unwrap();
+    assert_eq!(dfa.start, 0);
+    assert_eq!(dfa.alphabet, vec!['a', 'b']);
+    // the initial state, the others in document order, then the added sink state
+    assert_eq!(dfa.accepting, vec![false, false, true, false]);
+    assert_eq!(dfa.transition.get(&(0, 'b')), Some(&3));
+    assert!(!dfa.partial);
+    assert_same_language(&dfa, &DFA::open_dfa_file("begins_with_ab.dfa").unwrap(), 5);
+}
+
+#[test]
+fn to_jff_round_trip() {
+    let dfa = DFA::open_dfa_file("begins_with_ab.dfa").unwrap();
+    let jff = dfa.to_jff();
+    assert!(
+        jff.contains("\t\t<state id=\"0\" name=\"q0\">\n\t\t\t<x>100.0</x>\n\t\t\t<y>100.0</y>\n\t\t\t<initial/>\n")
+    );
+    assert_eq!(DFA::from_jff(&jff).unwrap(), dfa);
+
+    // the start state is moved to 0 on the way back in
+    let moved = dfa.with_start(1).unwrap();
+    let read = DFA::from_jff(&moved.to_jff()).unwrap();
+    assert_eq!(read.start, 0);
+    assert_eq!(read.states, moved.states);
+    assert_same_language(&read, &moved, 5);
+
+    // symbols with a meaning in xml are escaped
+    let odd = dfa_gen::modulo_n(&['<', '&', '"', 'x'], &['&'], 1, 3).unwrap();
+    assert_eq!(DFA::from_jff(&odd.to_jff()).unwrap(), odd);
+}
+
+#[test]
+fn invalid_files() {
+    assert!(matches!(DFA::from_jff("<structure>"), Err(JffError::Xml(_))));
+    assert!(matches!(
+        error("<type>fa</type>", "<type>pda</type>"),
+        JffError::NotFiniteAutomaton(kind) if kind == "pda"
+    ));
+    assert!(matches!(error("\t\t\t<initial/>\n", ""), JffError::NoInitialState));
+    assert!(matches!(
+        error("\t\t\t<final/>\n", "\t\t\t<initial/>\n"),
+        JffError::MultipleInitialStates
+    ));
+    assert!(matches!(
+        error("id=\"9\"", "id=\"7\""),
+        JffError::InvalidState(id) if id == "7"
+    ));
+    assert!(matches!(
+        error("<to>7</to>", "<to>8</to>"),
+        JffError::InvalidState(id) if id == "8"
+    ));
+    assert!(matches!(
+        error("<read>a</read>", "<read/>"),
+        JffError::EmptyTransition(id) if id == "3"
+    ));
+    assert!(matches!(
+        error("<read>a</read>", "<read>ab</read>"),
+        JffError::MultiCharacterSymbol(s) if s == "ab"
+    ));
+    let nondeterministic = error(
+        "<to>9</to>\n\t\t\t<read>b</read>\n\t\t</transition>\n\t</automaton>",
+        "<to>7</to>\n\t\t\t<read>a</read>\n\t\t</transition>\n\t</automaton>",
+    );
+    assert!(matches!(&nondeterministic, JffError::Nondeterministic((id, 'a')) if id == "9"));
+    assert_eq!(
+        nondeterministic.to_string(),
+        "nondeterministic: two transitions for character a from state 9"
+    );
+}
+
+#[test]
+fn jff_files_are_read_and_written_by_extension() {
+    let dir = env::temp_dir().join(format!("automata_jff_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let jflap = dir.join("jflap.jff");
+    fs::write(&jflap, JFLAP).unwrap();
+    let jflap = jflap.to_str().unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["evaluate", jflap, "abba", "ba"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "abba: true\nba: false\n");
+
+    let path = dir.join("saved.jff");
+    let path = path.to_str().unwrap();
+    let dfa = DFA::open_dfa_file(jflap).unwrap();
+    dfa.save_dfa_file(path).unwrap();
+    assert_eq!(fs::read_to_string(path).unwrap(), dfa.to_jff());
+    assert_eq!(DFA::open_dfa_file(path).unwrap(), dfa);
+
+    fs::write(path, JFLAP.replace("<type>fa</type>", "<type>turing</type>")).unwrap();
+    assert!(matches!(
+        DFA::open_dfa_file(path),
+        Err(DFAFileError::Jff(JffError::NotFiniteAutomaton(_)))
+    ));
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["info", path])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("unable to open file {path}: expected a finite automaton (type fa), found type turing\n")
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,279 @@
+mod common;
+
+use std::{env, fs, process::Command};
+
+use automata::{
+    dfa::DFA,
+    dfa_gen,
+    learn::{self, CommandOracle, EquivalenceOracle, MembershipOracle, MergeHeuristic, RandomSampling, WMethod},
+};
+use common::assert_same_language;
+
+// the languages L* is checked against
+fn targets() -> Vec<DFA> {
+    vec![
+        DFA::open_dfa_file("begins_with_ab.dfa").unwrap(),
+        dfa_gen::ends_wtih(&['a', 'b'], "aba").unwrap(),
+        dfa_gen::modulo_n(&['a', 'b', 'c'], &['a', 'c'], 2, 5).unwrap(),
+        dfa_gen::contains_substring(&['a', 'b'], "bb").unwrap(),
+        dfa_gen::empty(&['a', 'b']),
+    ]
+}
+
+fn available(tool: &str) -> bool {
+    let found = Command::new(tool)
+        .arg("-c")
+        .arg("true")
+        .status()
+        .is_ok_and(|s| s.success());
+    if !found {
+        eprintln!("{tool} not found, skipping");
+    }
+    found
+}
+
+#[test]
+fn command_errors_are_kept() {
+    let mut oracle = CommandOracle::new(vec![String::from("no_such_command_for_automata")]);
+    assert!(!oracle.query("ab"));
+    assert!(oracle
+        .error
+        .as_ref()
+        .unwrap()
+        .starts_with("unable to run no_such_command_for_automata: "));
+    let mut oracle = CommandOracle::new(vec![]);
+    assert!(!oracle.query(""));
+    assert_eq!(oracle.error.as_deref(), Some("no command given"));
+}
+
+#[test]
+fn programs_may_ignore_their_input() {
+    if !available("sh") {
+        return;
+    }
+    // longer than a pipe buffer, so writing it fails once the program has exited
+    let long = "a".repeat(1 << 20);
+    for (script, expected) in [("exit 0", true), ("exit 1", false)] {
+        let mut oracle = CommandOracle::new(["sh", "-c", script].map(String::from).to_vec());
+        assert_eq!(oracle.query(&long), expected);
+        assert_eq!(oracle.error, None);
+    }
+}
+
+#[test]
+fn learn_command_reports_a_missing_program() {
+    let dir = env::temp_dir().join(format!("automata_learn_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("learned.dfa");
+    let result = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .arg("learn")
+        .arg(&output)
+        .args(["a,b", "--", "no_such_command_for_automata"])
+        .output()
+        .unwrap();
+    assert!(!result.status.success());
+    assert!(String::from_utf8(result.stdout)
+        .unwrap()
+        .starts_with("unable to run no_such_command_for_automata: "));
+    assert!(!output.exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// an even number of a's and the length a multiple of three
+fn even_a_length_three(s: &str) -> bool {
+    s.matches('a').count().is_multiple_of(2) && s.len().is_multiple_of(3)
+}
+
+#[test]
+fn lstar_learns_the_minimal_dfa() {
+    for target in targets() {
+        let mut membership = target.clone();
+        // enough extra states for the one state first hypothesis
+        let mut equivalence = WMethod {
+            extra_states: target.states as usize,
+        };
+        let learned = learn::lstar(&target.alphabet, &mut membership, &mut equivalence);
+        assert_eq!(learned.states, target.optimize().states);
+        assert_same_language(&learned, &target, 6);
+    }
+}
+
+#[test]
+fn w_method_finds_a_difference_within_its_bound() {
+    let hypothesis = dfa_gen::all_strings(&['a', 'b']);
+    let mut target = dfa_gen::ends_wtih(&['a', 'b'], "aba").unwrap();
+    let counterexample = WMethod { extra_states: 3 }
+        .find_counterexample(&hypothesis, &mut target)
+        .unwrap();
+    assert!(!target.evaluate(&counterexample).unwrap());
+    let mut same = hypothesis.clone();
+    assert_eq!(
+        WMethod { extra_states: 2 }.find_counterexample(&hypothesis, &mut same),
+        None
+    );
+}
+
+#[test]
+fn random_sampling_is_reproducible() {
+    let hypothesis = dfa_gen::empty(&['a', 'b']);
+    let mut target = dfa_gen::contains_substring(&['a', 'b'], "bab").unwrap();
+    let mut sample = |seed| {
+        let mut oracle = RandomSampling {
+            samples: 200,
+            max_length: 10,
+            seed,
+        };
+        oracle.find_counterexample(&hypothesis, &mut target)
+    };
+    let first = sample(7).unwrap();
+    assert!(first.contains("bab"));
+    assert_eq!(sample(7), Some(first));
+    let mut never = RandomSampling {
+        samples: 200,
+        max_length: 2,
+        seed: 7,
+    };
+    assert_eq!(never.find_counterexample(&hypothesis, &mut target), None);
+}
+
+#[test]
+fn closures_and_combined_oracles() {
+    let mut queries = 0;
+    let mut membership = |s: &str| {
+        queries += 1;
+        even_a_length_three(s)
+    };
+    let mut equivalence = (
+        RandomSampling {
+            samples: 0,
+            max_length: 0,
+            seed: 1,
+        },
+        WMethod { extra_states: 2 },
+    );
+    let learned = learn::lstar(&['a', 'b'], &mut membership, &mut equivalence);
+    assert_eq!(learned.states, 6);
+    for s in common::strings(&['a', 'b'], 6) {
+        assert_eq!(learned.evaluate(&s).unwrap(), even_a_length_three(&s), "{s:?}");
+    }
+    // repeated questions are answered from the observation table's cache
+    let distinct = common::strings(&['a', 'b'], 12).len();
+    assert!(queries < distinct);
+}
+
+#[test]
+fn learn_command() {
+    if !available("sh") {
+        return;
+    }
+    let dir = env::temp_dir().join(format!("automata_learn_command_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("begins_with_ab.dfa");
+    let result = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .arg("learn")
+        .arg(&output)
+        .args(["a,b", "--", "sh", "-c", "grep -q '^ab'"])
+        .output()
+        .unwrap();
+    assert!(result.status.success());
+    let learned = DFA::open_dfa_file(output.to_str().unwrap()).unwrap();
+    assert_same_language(&learned, &DFA::open_dfa_file("begins_with_ab.dfa").unwrap(), 6);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// an even number of a's
+const POSITIVE: [&str; 8] = ["", "aa", "b", "bb", "aab", "aba", "baa", "abab"];
+const NEGATIVE: [&str; 6] = ["a", "ab", "ba", "aaa", "bab", "abb"];
+
+#[test]
+fn passive_learning_is_consistent_with_the_samples() {
+    for heuristic in [MergeHeuristic::Rpni, MergeHeuristic::Edsm] {
+        let dfa = learn::passive(&POSITIVE, &NEGATIVE, &['a', 'b'], heuristic).unwrap();
+        for s in POSITIVE {
+            assert!(dfa.evaluate(s).unwrap(), "{heuristic:?} {s:?}");
+        }
+        for s in NEGATIVE {
+            assert!(!dfa.evaluate(s).unwrap(), "{heuristic:?} {s:?}");
+        }
+        let even = dfa_gen::modulo_n(&['a', 'b'], &['a'], 0, 2).unwrap();
+        assert_same_language(&dfa, &even, 6);
+    }
+    assert_same_language(
+        &learn::rpni(&POSITIVE, &NEGATIVE, &['a', 'b']).unwrap(),
+        &learn::edsm(&POSITIVE, &NEGATIVE, &['a', 'b']).unwrap(),
+        6,
+    );
+}
+
+#[test]
+fn passive_learning_edge_cases() {
+    // no negative samples, so everything merges into one accepting state
+    let dfa = learn::rpni(&["a", "bb"], &[], &['a', 'b']).unwrap();
+    assert_eq!(dfa.states, 1);
+    assert!(dfa.evaluate("abba").unwrap());
+    // strings off the prefix tree go to a rejecting sink
+    let dfa = learn::edsm(&[], &["a"], &['a', 'b']).unwrap();
+    assert!(!dfa.evaluate("").unwrap());
+    assert!(!dfa.evaluate("b").unwrap());
+    // a letter of the alphabet that no sample uses
+    let dfa = learn::rpni(&["a"], &[""], &['a', 'b', 'c']).unwrap();
+    assert_eq!(dfa.alphabet, vec!['a', 'b', 'c']);
+    assert!(dfa.evaluate("a").unwrap());
+    assert!(!dfa.evaluate("").unwrap());
+}
+
+#[test]
+fn conflicting_samples_are_an_error() {
+    for heuristic in [MergeHeuristic::Rpni, MergeHeuristic::Edsm] {
+        let result = learn::passive(&["ab", "b"], &["a", "b"], &['a', 'b'], heuristic);
+        assert_eq!(result.unwrap_err(), "b");
+    }
+}
+
+#[test]
+fn learn_from_command() {
+    let dir = env::temp_dir().join(format!("automata_learn_from_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let samples = dir.join("samples.txt");
+    let output = dir.join("even.dfa");
+    let run = |args: &[&str], contents: &str| {
+        fs::write(&samples, contents).unwrap();
+        Command::new(env!("CARGO_BIN_EXE_dfa"))
+            .arg("learn-from")
+            .args(args)
+            .arg(&output)
+            .arg(&samples)
+            .output()
+            .unwrap()
+    };
+    let mut contents = String::new();
+    for s in POSITIVE {
+        contents.push_str(&format!("+{s}\r\n"));
+    }
+    for s in NEGATIVE {
+        contents.push_str(&format!("-{s}\n\n"));
+    }
+    let even = dfa_gen::modulo_n(&['a', 'b'], &['a'], 0, 2).unwrap();
+    for args in [&[][..], &["--edsm"][..]] {
+        assert!(run(args, &contents).status.success());
+        let dfa = DFA::open_dfa_file(output.to_str().unwrap()).unwrap();
+        assert_same_language(&dfa, &even, 6);
+    }
+
+    let result = run(&[], "+a\n-a\n");
+    assert!(!result.status.success());
+    assert_eq!(
+        String::from_utf8(result.stdout).unwrap(),
+        format!(
+            "\"a\" is both a positive and a negative sample in {}\n",
+            samples.display()
+        )
+    );
+    let result = run(&[], "+a\nb\n");
+    assert!(!result.status.success());
+    assert_eq!(
+        String::from_utf8(result.stdout).unwrap(),
+        format!("line 2 of {} does not start with + or -\n", samples.display())
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,140 @@
+use std::{env, fs, process::Command};
+
+use automata::{
+    dfa::DFA,
+    dfa_gen,
+    lexer::{LexError, Lexer},
+};
+
+// one or more of the given characters
+fn plus(chars: &[char]) -> DFA {
+    let mut dfa = DFA::new();
+    dfa.alphabet = chars.to_vec();
+    dfa.states = 2;
+    dfa.accepting = vec![false, true];
+    for &char in chars {
+        dfa.transition.insert((0, char), 1);
+        dfa.transition.insert((1, char), 1);
+    }
+    dfa
+}
+
+fn rules(keyword_first: bool) -> Vec<(String, DFA)> {
+    let keyword = (String::from("IF"), dfa_gen::only_string(&['i', 'f'], "if").unwrap());
+    let identifier = (String::from("IDENT"), plus(&['i', 'f', 'x']));
+    let space = (String::from("WS"), plus(&[' ']));
+    match keyword_first {
+        true => vec![keyword, identifier, space],
+        false => vec![identifier, keyword, space],
+    }
+}
+
+fn names(tokens: &[(String, std::ops::Range<usize>)]) -> Vec<&str> {
+    tokens.iter().map(|(name, _)| name.as_str()).collect()
+}
+
+#[test]
+fn longest_match_wins() {
+    let lexer = Lexer::new(&rules(true)).unwrap();
+    let tokens = lexer.tokenize("if iff x").unwrap();
+    assert_eq!(
+        tokens,
+        vec![
+            (String::from("IF"), 0..2),
+            (String::from("WS"), 2..3),
+            (String::from("IDENT"), 3..6),
+            (String::from("WS"), 6..7),
+            (String::from("IDENT"), 7..8),
+        ]
+    );
+}
+
+#[test]
+fn first_rule_wins_a_tie() {
+    let keyword_first = Lexer::new(&rules(true)).unwrap();
+    let identifier_first = Lexer::new(&rules(false)).unwrap();
+    assert_eq!(names(&keyword_first.tokenize("if").unwrap()), vec!["IF"]);
+    assert_eq!(names(&identifier_first.tokenize("if").unwrap()), vec!["IDENT"]);
+}
+
+#[test]
+fn empty_input_has_no_tokens() {
+    assert_eq!(Lexer::new(&rules(true)).unwrap().tokenize("").unwrap(), vec![]);
+}
+
+#[test]
+fn untokenizable_input_reports_its_position() {
+    let lexer = Lexer::new(&rules(true)).unwrap();
+    assert!(matches!(lexer.tokenize("if ?"), Err(LexError::Untokenizable(3))));
+    assert!(matches!(lexer.tokenize("é"), Err(LexError::Untokenizable(0))));
+}
+
+#[test]
+fn partial_rules_reject_at_missing_transitions() {
+    // the rules without their dead states, so most transitions are missing
+    let identifier = plus(&['i', 'f', 'x']).trim();
+    let keyword = dfa_gen::only_string(&['i', 'f'], "if").unwrap().trim();
+    assert!(keyword.partial && !keyword.transition.contains_key(&(0, 'f')));
+    let rules = vec![
+        (String::from("IF"), keyword),
+        (String::from("IDENT"), identifier),
+        (String::from("WS"), plus(&[' '])),
+    ];
+    let lexer = Lexer::new(&rules).unwrap();
+    assert_eq!(
+        names(&lexer.tokenize("if fix iff").unwrap()),
+        vec!["IF", "WS", "IDENT", "WS", "IDENT"]
+    );
+    assert!(matches!(lexer.tokenize("if ?"), Err(LexError::Untokenizable(3))));
+}
+
+#[test]
+fn a_rule_without_states_is_an_error() {
+    let mut rules = rules(true);
+    rules.push((String::from("BAD"), DFA::new()));
+    assert!(matches!(Lexer::new(&rules), Err(LexError::EmptyRule(name)) if name == "BAD"));
+}
+
+#[test]
+fn spec_files_and_the_tokenize_command() {
+    let dir = env::temp_dir().join(format!("automata_lexer_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for (name, dfa) in rules(true) {
+        dfa.save_dfa_file(dir.join(format!("{name}.json")).to_str().unwrap())
+            .unwrap();
+    }
+    let spec = dir.join("tokens.spec");
+    fs::write(
+        &spec,
+        "% keywords first\nIF IF.json\n\nIDENT IDENT.json\nWS WS.json % spaces\n",
+    )
+    .unwrap();
+    let lexer = Lexer::open_spec_file(spec.to_str().unwrap()).unwrap();
+    assert_eq!(names(&lexer.tokenize("x if").unwrap()), vec!["IDENT", "WS", "IF"]);
+
+    let input = dir.join("input.txt");
+    fs::write(&input, "fi if").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .arg("tokenize")
+        .arg(&spec)
+        .arg(&input)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "IDENT\t0..2\t\"fi\"\nWS\t2..3\t\" \"\nIF\t3..5\t\"if\"\n"
+    );
+
+    fs::write(&spec, "IF IF.json\nIDENT\n").unwrap();
+    assert!(matches!(
+        Lexer::open_spec_file(spec.to_str().unwrap()),
+        Err(LexError::InvalidSpecLine(2))
+    ));
+    fs::write(&spec, "IF missing.json\n").unwrap();
+    assert!(matches!(
+        Lexer::open_spec_file(spec.to_str().unwrap()),
+        Err(LexError::InvalidDfa(_))
+    ));
+    fs::remove_dir_all(dir).unwrap();
+}
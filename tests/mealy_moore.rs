@@ -0,0 +1,129 @@
+mod common;
+
+use automata::{
+    dfa::{DFAEvaluationError, DFA},
+    mealy::MealyMachine,
+    moore::MooreMachine,
+};
+use common::{assert_same_language, strings};
+
+// outputs whether an even or odd number of a's has been read
+const PARITY: &str = "2\neven,odd\na,b\n1,0\n0,1\n";
+
+// outputs the previous character, starting with x
+const DELAY: &str = "3\na,b\n1/x,2/x\n1/a,2/a\n1/b,2/b\n";
+
+#[test]
+fn missing_transitions_round_trip() {
+    let mut moore = MooreMachine::from_string(String::from(PARITY));
+    moore.transition.remove(&(1, 'b'));
+    let text = moore.to_string();
+    assert!(text.contains("\n0,- %1:\n"));
+    assert_eq!(MooreMachine::from_string(text).transition, moore.transition);
+
+    let mut mealy = MealyMachine::from_string(String::from(DELAY));
+    mealy.transition.remove(&(0, 'b'));
+    mealy.output.remove(&(0, 'b'));
+    let text = mealy.to_string();
+    assert!(text.contains("\n1/x,- %0:\n"));
+    let read = MealyMachine::from_string(text);
+    assert_eq!(read.transition, mealy.transition);
+    assert_eq!(read.output, mealy.output);
+}
+
+#[test]
+fn out_of_range_targets_are_an_error() {
+    let mut moore = MooreMachine::from_string(String::from(PARITY));
+    moore.transition.insert((1, 'a'), 5);
+    assert!(matches!(moore.run("aa"), Err(DFAEvaluationError::InvalidState(5))));
+    moore.transition.insert((0, 'b'), -1);
+    assert!(matches!(moore.run("b"), Err(DFAEvaluationError::InvalidState(-1))));
+
+    let mut mealy = MealyMachine::from_string(String::from(DELAY));
+    mealy.transition.insert((1, 'b'), 3);
+    assert!(matches!(mealy.run("ab"), Err(DFAEvaluationError::InvalidState(3))));
+}
+
+#[test]
+fn outputs_with_separators_round_trip() {
+    let awkward = ["a,b", "50%", " padded ", "x/y", ""];
+    let mut moore = MooreMachine::from_string(String::from(PARITY));
+    let mut mealy = MealyMachine::from_string(String::from(DELAY));
+    for (i, output) in awkward.iter().enumerate() {
+        moore.output[i % 2] = output.to_string();
+        mealy.output.insert((i as i32 % 2, 'a'), output.to_string());
+        let read = MooreMachine::from_string(moore.to_string());
+        assert_eq!(read.output, moore.output);
+        assert_eq!(read.run("ab").unwrap(), moore.run("ab").unwrap());
+        let read = MealyMachine::from_string(mealy.to_string());
+        assert_eq!(read.output, mealy.output);
+    }
+}
+
+// PARITY with every state doubled, plus an unreachable state
+const DOUBLED_PARITY: &str = "5\neven,odd,even,odd,odd\na,b\n1,2\n2,1\n3,0\n0,3\n4,4\n";
+
+fn words(s: &str) -> Vec<String> {
+    s.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn moore_run_outputs_one_more_than_the_input() {
+    let moore = MooreMachine::from_string(String::from(PARITY));
+    assert_eq!(moore.run("").unwrap(), words("even"));
+    assert_eq!(moore.run("aab").unwrap(), words("even odd even even"));
+    assert!(matches!(moore.run("ac"), Err(DFAEvaluationError::InvalidChar('c'))));
+    assert!(matches!(
+        MooreMachine::from_string(String::from("0\n\na\n")).run(""),
+        Err(DFAEvaluationError::InvalidState(0))
+    ));
+}
+
+#[test]
+fn mealy_run_outputs_one_per_character() {
+    let mealy = MealyMachine::from_string(String::from(DELAY));
+    assert!(mealy.run("").unwrap().is_empty());
+    assert_eq!(mealy.run("abba").unwrap(), words("x a b b"));
+    assert!(matches!(mealy.run("a!"), Err(DFAEvaluationError::InvalidChar('!'))));
+}
+
+#[test]
+fn moore_and_mealy_conversions_agree() {
+    let moore = MooreMachine::from_string(String::from(PARITY));
+    let mealy = MealyMachine::from_string(String::from(DELAY));
+    let back = mealy.to_moore(String::from("x"));
+    for s in strings(&['a', 'b'], 5) {
+        assert_eq!(moore.to_mealy().run(&s).unwrap(), moore.run(&s).unwrap()[1..]);
+        assert_eq!(back.run(&s).unwrap()[1..], mealy.run(&s).unwrap());
+        assert_eq!(back.run(&s).unwrap()[0], "x");
+    }
+}
+
+#[test]
+fn minimize_merges_equivalent_states() {
+    let moore = MooreMachine::from_string(String::from(DOUBLED_PARITY));
+    let minimal = moore.minimize();
+    assert_eq!(minimal.states, 2);
+    assert_eq!(minimal.output, words("even odd"));
+    let mealy = moore.to_mealy();
+    let minimal_mealy = mealy.minimize();
+    assert_eq!(minimal_mealy.states, 2);
+    for s in strings(&['a', 'b'], 5) {
+        assert_eq!(minimal.run(&s).unwrap(), moore.run(&s).unwrap());
+        assert_eq!(minimal_mealy.run(&s).unwrap(), mealy.run(&s).unwrap());
+    }
+    // the previous character is remembered, so nothing can be merged
+    let delay = MealyMachine::from_string(String::from(DELAY));
+    assert_eq!(delay.minimize().states, 3);
+}
+
+#[test]
+fn dfas_are_moore_machines_with_boolean_outputs() {
+    let dfa = DFA::open_dfa_file("begins_with_ab.dfa").unwrap();
+    let moore = MooreMachine::from_dfa(&dfa);
+    for s in strings(&dfa.alphabet, 5) {
+        assert_eq!(moore.run(&s).unwrap().last(), Some(&dfa.evaluate(&s).unwrap()));
+    }
+    assert_same_language(&dfa, &moore.to_dfa(), 5);
+    assert_same_language(&dfa, &moore.minimize().to_dfa(), 5);
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use std::{env, fs, process::Command};
+
+use automata::{
+    dfa::{DFAEvaluationError, DFA},
+    dfa_binary::DfaView,
+};
+use common::{assert_same_language, strings};
+
+// 1 has no transition on b, and 2 accepts everything
+const EXAMPLE: &str = "3\n1,2\na,b\n1,2\n1,-\n2,2\n";
+
+fn partial_example() -> DFA {
+    let mut dfa = DFA::from_v1_string(EXAMPLE).unwrap();
+    dfa.partial = true;
+    dfa
+}
+
+#[test]
+fn optimize_keeps_the_language_of_a_partial_dfa() {
+    let dfa = partial_example();
+    let optimized = dfa.optimize();
+    assert_eq!(dfa.evaluate("bb").ok(), Some(true));
+    assert_eq!(optimized.evaluate("bb").ok(), Some(true));
+    assert_eq!(optimized.evaluate("ab").ok(), Some(false));
+    assert!(!dfa.is_permanently_accepting(1));
+    assert!(dfa.is_permanently_accepting(2));
+    assert_same_language(&dfa, &optimized, 5);
+    assert_same_language(&dfa, &dfa.complete().optimize(), 5);
+}
+
+#[test]
+fn missing_transitions_are_an_error_unless_partial() {
+    let dfa = DFA::from_v1_string(EXAMPLE).unwrap();
+    assert!(!dfa.partial);
+    assert!(matches!(
+        dfa.evaluate("ab"),
+        Err(DFAEvaluationError::NoTransition((1, 'b')))
+    ));
+    assert_eq!(partial_example().evaluate("ab").ok(), Some(false));
+}
+
+#[test]
+fn trace_stops_at_a_missing_transition() {
+    let dfa = partial_example();
+    assert_eq!(dfa.trace("aba").unwrap(), vec![(0, 'a', 1)]);
+    let table = dfa.trace_to_string("aba");
+    assert!(table.contains("1\t0\ta\t1\n"));
+    assert!(table.ends_with("false"));
+}
+
+#[test]
+fn partial_dfas_round_trip_through_json_and_v2() {
+    let dfa = partial_example();
+    for read in [
+        DFA::from_json(&dfa.to_json()).unwrap(),
+        DFA::from_v2_string(&dfa.to_v2_string()).unwrap(),
+    ] {
+        assert!(read.partial);
+        assert_eq!(read.states, dfa.states);
+        assert_same_language(&dfa, &read, 5);
+    }
+}
+
+#[test]
+fn binary_view_agrees_with_evaluate() {
+    let dfa = partial_example();
+    let bytes = dfa.to_binary();
+    let view = DfaView::new(&bytes).unwrap();
+    for s in strings(&dfa.alphabet, 5) {
+        assert_eq!(view.evaluate(&s).ok(), dfa.evaluate(&s).ok(), "{s:?}");
+    }
+}
+
+#[test]
+fn trimmed_dfa_files_stay_partial() {
+    let dir = env::temp_dir().join(format!("automata_partial_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("trimmed.dfa");
+    let path = path.to_str().unwrap();
+    let trimmed = DFA::open_dfa_file("begins_with_ab.dfa").unwrap().trim();
+    trimmed.save_dfa_file(path).unwrap();
+    let read = DFA::open_dfa_file(path).unwrap();
+    assert!(read.partial);
+    assert_eq!(read.evaluate("b").ok(), Some(false));
+    assert_same_language(&read, &trimmed, 5);
+
+    // complete automata are still written in version 1
+    trimmed.complete().save_dfa_file(path).unwrap();
+    assert_eq!(fs::read_to_string(path).unwrap(), trimmed.complete().to_string());
+
+    let command = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_dfa")).args(args).output().unwrap();
+    assert!(command(&["trim", path, "begins_with_ab.dfa"]).status.success());
+    let output = command(&["evaluate", path, "b", "abb"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "b: false\nabb: true\n");
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use std::process::Command;
+
+use automata::pda::{Acceptance, Configuration, PDAEvaluationError, PDA};
+use common::strings;
+
+fn balanced(s: &str) -> bool {
+    let mut depth = 0;
+    for c in s.chars() {
+        depth += if c == '(' { 1 } else { -1 };
+        if depth < 0 {
+            return false;
+        }
+    }
+    depth == 0
+}
+
+// even length palindromes, guessing the middle with an epsilon move
+fn palindromes() -> PDA {
+    let mut pda = PDA::new();
+    pda.alphabet = vec!['a', 'b'];
+    pda.stack_alphabet = vec!['Z', 'A', 'B'];
+    pda.states = 3;
+    pda.accepting = vec![false, false, true];
+    for top in ['Z', 'A', 'B'] {
+        pda.add_transition(0, Some('a'), top, 0, &format!("A{top}"));
+        pda.add_transition(0, Some('b'), top, 0, &format!("B{top}"));
+        pda.add_transition(0, None, top, 1, &top.to_string());
+    }
+    pda.add_transition(1, Some('a'), 'A', 1, "");
+    pda.add_transition(1, Some('b'), 'B', 1, "");
+    pda.add_transition(1, None, 'Z', 2, "Z");
+    pda
+}
+
+fn is_palindrome(s: &str) -> bool {
+    s.len().is_multiple_of(2) && s.chars().eq(s.chars().rev())
+}
+
+#[test]
+fn balanced_parentheses_file() {
+    let pda = PDA::open_pda_file("balanced_parentheses").unwrap();
+    assert_eq!(pda.acceptance, Acceptance::FinalState);
+    for s in strings(&['(', ')'], 8) {
+        assert_eq!(pda.evaluate(&s).unwrap(), balanced(&s), "{s:?}");
+    }
+    assert!(matches!(pda.evaluate("(x)"), Err(PDAEvaluationError::InvalidChar('x'))));
+    assert_eq!(pda.evaluate_to_string("(x)"), "invalid character: x");
+}
+
+#[test]
+fn nondeterministic_palindromes() {
+    let pda = palindromes();
+    for s in strings(&['a', 'b'], 6) {
+        assert_eq!(pda.evaluate(&s).unwrap(), is_palindrome(&s), "{s:?}");
+    }
+}
+
+#[test]
+fn to_string_round_trip() {
+    for pda in [palindromes(), PDA::open_pda_file("balanced_parentheses.pda").unwrap()] {
+        let read = PDA::from_string(pda.to_string());
+        assert_eq!(read.states, pda.states);
+        assert_eq!(read.accepting, pda.accepting);
+        assert_eq!(read.alphabet, pda.alphabet);
+        assert_eq!(read.stack_alphabet, pda.stack_alphabet);
+        assert_eq!(read.acceptance, pda.acceptance);
+        assert_eq!(read.transition, pda.transition);
+    }
+}
+
+#[test]
+fn empty_stack_acceptance_accepts_the_same_strings() {
+    let pda = PDA::open_pda_file("balanced_parentheses.pda").unwrap();
+    let empty = pda.to_empty_stack_acceptance();
+    assert_eq!(empty.acceptance, Acceptance::EmptyStack);
+    assert!(empty.to_string().contains("\nempty %acceptance\n"));
+    for s in strings(&['(', ')'], 6) {
+        assert_eq!(empty.evaluate(&s).unwrap(), balanced(&s), "{s:?}");
+    }
+    let palindromes = palindromes();
+    let empty = palindromes.to_empty_stack_acceptance();
+    for s in strings(&['a', 'b'], 4) {
+        assert_eq!(empty.evaluate(&s).unwrap(), is_palindrome(&s), "{s:?}");
+    }
+    let read = PDA::from_string(empty.to_string());
+    assert_eq!(read.acceptance, Acceptance::EmptyStack);
+    assert!(read.evaluate("abba").unwrap());
+}
+
+#[test]
+fn find_run_returns_a_shortest_run() {
+    let pda = PDA::open_pda_file("balanced_parentheses.pda").unwrap();
+    let config = |state, position, stack: &str| Configuration {
+        state,
+        position,
+        stack: stack.to_string(),
+    };
+    assert_eq!(
+        pda.find_run("()").unwrap().unwrap(),
+        vec![
+            config(0, 0, "Z"),
+            config(0, 1, "PZ"),
+            config(0, 2, "Z"),
+            config(1, 2, "Z")
+        ]
+    );
+    assert_eq!(pda.find_run(")(").unwrap(), None);
+    assert_eq!(
+        pda.trace_to_string("()"),
+        "step\tstate\tremaining\tstack\n0\t0\t()\tZ\n1\t0\t)\tPZ\n2\t0\t\tZ\n3\t1\t\tZ\ntrue"
+    );
+    assert_eq!(pda.trace_to_string(")"), "false");
+}
+
+#[test]
+fn endless_epsilon_pushes_stop_at_the_limit() {
+    let mut pda = PDA::new();
+    pda.alphabet = vec!['a'];
+    pda.stack_alphabet = vec!['Z'];
+    pda.states = 2;
+    pda.accepting = vec![false, true];
+    pda.add_transition(0, None, 'Z', 0, "ZZ");
+    assert!(matches!(
+        pda.find_run_with_limit("a", 50),
+        Err(PDAEvaluationError::SearchLimitReached(50))
+    ));
+}
+
+#[test]
+fn evaluate_command() {
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["evaluate", "--trace", "balanced_parentheses.pda", "()", ")"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "():\nstep\tstate\tremaining\tstack\n0\t0\t()\tZ\n1\t0\t)\tPZ\n2\t0\t\tZ\n3\t1\t\tZ\ntrue\n\n):\nfalse\n\n"
+    );
+}
@@ -0,0 +1,61 @@
+use std::{env, fs, process::Command};
+
+use automata::{dfa::DFA, dfa_gen};
+
+// a followed by one or more b
+fn ab_plus() -> DFA {
+    DFA::from_v1_string("4\n2\na,b\n1,3\n3,2\n3,2\n3,3\n").unwrap()
+}
+
+#[test]
+fn find_returns_the_leftmost_longest_match() {
+    let dfa = ab_plus();
+    assert_eq!(dfa.find("xxabbbyab"), Some(2..6));
+    assert_eq!(dfa.find("aab"), Some(1..3));
+    assert_eq!(dfa.find("aaa"), None);
+    assert_eq!(dfa.find(""), None);
+}
+
+#[test]
+fn find_iter_does_not_overlap() {
+    let dfa = ab_plus();
+    assert_eq!(dfa.find_iter("xxabbbyab").collect::<Vec<_>>(), vec![2..6, 7..9]);
+    assert_eq!(dfa.find_iter("abab").collect::<Vec<_>>(), vec![0..2, 2..4]);
+}
+
+#[test]
+fn spans_are_byte_offsets() {
+    assert_eq!(ab_plus().find_iter("éab€abb").collect::<Vec<_>>(), vec![2..4, 7..10]);
+}
+
+#[test]
+fn empty_matches_advance_by_one_character() {
+    let dfa = dfa_gen::all_strings(&['a']);
+    assert_eq!(dfa.find_iter("béa").collect::<Vec<_>>(), vec![0..0, 1..1, 3..4, 4..4]);
+}
+
+#[test]
+fn is_match_anywhere() {
+    let dfa = ab_plus();
+    assert!(dfa.is_match_anywhere("zzzab"));
+    assert!(!dfa.is_match_anywhere("ba ba"));
+    assert!(!DFA::new().is_match_anywhere("anything"));
+}
+
+#[test]
+fn grep_prints_matching_lines_with_spans() {
+    let path = env::temp_dir().join(format!("automata_grep_{}.txt", std::process::id()));
+    fs::write(&path, "no match\nxab and abb\n\nab\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .arg("grep")
+        .arg("begins_with_ab.dfa")
+        .arg(&path)
+        .output()
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2:1..3,8..11: xab and abb\n4:0..2: ab\n"
+    );
+}
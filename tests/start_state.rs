@@ -0,0 +1,136 @@
+mod common;
+
+use std::{env, fs, process::Command};
+
+use automata::{dfa::DFA, dfa_gen};
+use common::{assert_same_language, strings};
+
+fn begins_with_ab() -> DFA {
+    DFA::open_dfa_file("begins_with_ab.dfa").unwrap()
+}
+
+// the strings state 1 of begins_with_ab accepts: those that begin with b
+fn begins_with_b() -> DFA {
+    dfa_gen::begins_with(&['a', 'b'], "b").unwrap()
+}
+
+#[test]
+fn with_start_answers_what_a_state_accepts() {
+    let dfa = begins_with_ab().with_start(1).unwrap();
+    assert_eq!(dfa.start, 1);
+    assert_same_language(&dfa, &begins_with_b(), 5);
+    assert!(dfa.evaluate("").is_ok_and(|a| !a));
+    assert_eq!(dfa.trace("ba").unwrap(), vec![(1, 'b', 2), (2, 'a', 2)]);
+    assert!(begins_with_ab().with_start(2).unwrap().evaluate("").unwrap());
+    assert!(begins_with_ab().with_start(4).is_err());
+    assert!(begins_with_ab().with_start(-1).is_err());
+}
+
+#[test]
+fn reachability_starts_at_the_start_state() {
+    let dfa = begins_with_ab().with_start(1).unwrap();
+    assert_eq!(dfa.get_unreachable_states(), vec![0]);
+    assert!(!dfa.is_empty());
+    assert!(begins_with_ab().with_start(3).unwrap().is_empty());
+
+    let reachable = dfa.remove_unreachable_states();
+    assert_eq!((reachable.states, reachable.start), (3, 0));
+    assert_same_language(&reachable, &dfa, 5);
+    let optimized = dfa.optimize();
+    assert_eq!(optimized.states, 3);
+    assert_same_language(&optimized, &dfa, 5);
+
+    // removing the start state makes its replacement the start
+    let removed = dfa.remove_state(1, 3).unwrap();
+    assert_eq!(removed.start, 2);
+    assert!(removed.is_empty());
+}
+
+#[test]
+fn products_start_at_the_pair_of_start_states() {
+    let from_one = begins_with_ab().with_start(1).unwrap();
+    // odd lengths from state 0, so even lengths from state 1
+    let even_length = dfa_gen::modulo_n(&['a', 'b'], &['a', 'b'], 1, 2)
+        .unwrap()
+        .with_start(1)
+        .unwrap();
+    let both = from_one.intersect(&even_length);
+    let either = from_one.union(&even_length);
+    let only_first = from_one.difference(&even_length);
+    for s in strings(&['a', 'b'], 5) {
+        let (b, even) = (s.starts_with('b'), s.len().is_multiple_of(2));
+        assert_eq!(both.evaluate(&s).unwrap(), b && even, "{s:?}");
+        assert_eq!(either.evaluate(&s).unwrap(), b || even, "{s:?}");
+        assert_eq!(only_first.evaluate(&s).unwrap(), b && !even, "{s:?}");
+    }
+    assert_same_language(&from_one.negation().negation(), &begins_with_b(), 5);
+}
+
+#[test]
+fn formats_keep_the_start_state() {
+    let mut dfa = begins_with_ab().with_start(1).unwrap();
+    dfa.names.insert(0, String::from("first"));
+    dfa.names.insert(1, String::from("second"));
+
+    let swapped = dfa.start_at_zero();
+    assert_eq!(swapped.start, 0);
+    assert_eq!(swapped.accepting, dfa.accepting);
+    assert_eq!(swapped.names.get(&0).map(String::as_str), Some("second"));
+    assert_eq!(swapped.transition.get(&(0, 'b')), Some(&2));
+    assert_eq!(swapped.transition.get(&(1, 'a')), Some(&0));
+    assert_same_language(&swapped, &dfa, 5);
+    assert_eq!(begins_with_ab().start_at_zero(), begins_with_ab());
+
+    // the .dfa format always starts at 0, version 2 and json name the start state
+    let v1 = DFA::from_string(dfa.to_string()).unwrap();
+    assert_eq!(v1.start, 0);
+    assert_same_language(&v1, &dfa, 5);
+    let v2 = dfa.to_v2_string();
+    assert!(v2.contains("\nstart q1\n"), "{v2}");
+    assert_eq!(DFA::from_v2_string(&v2).unwrap().start, 1);
+    assert_eq!(DFA::from_json(&dfa.to_json()).unwrap().start, 1);
+}
+
+#[test]
+fn generators_start_at_zero() {
+    for dfa in [
+        dfa_gen::begins_with(&['a', 'b'], "ab").unwrap(),
+        dfa_gen::ends_wtih(&['a', 'b'], "ab").unwrap(),
+        dfa_gen::modulo_n(&['a', 'b'], &['a'], 0, 3).unwrap(),
+        dfa_gen::only_string(&['a', 'b'], "ab").unwrap(),
+        dfa_gen::all_strings(&['a', 'b']),
+    ] {
+        assert_eq!(dfa.start, 0);
+        assert_eq!(dfa.get_unreachable_states(), Vec::<i32>::new());
+    }
+}
+
+#[test]
+fn commands_respect_the_start_state() {
+    let dir = env::temp_dir().join(format!("automata_start_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("from_one.json");
+    let path = path.to_str().unwrap();
+    begins_with_ab().with_start(1).unwrap().save_dfa_file(path).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["evaluate", path, "ba", "ab", ""])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "ba: true\nab: false\n: false\n"
+    );
+
+    let negated = dir.join("negated.dfa");
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .arg("negate")
+        .arg(&negated)
+        .arg(path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let negated = DFA::open_dfa_file(negated.to_str().unwrap()).unwrap();
+    assert_same_language(&negated, &begins_with_b().negation(), 5);
+    fs::remove_dir_all(dir).unwrap();
+}
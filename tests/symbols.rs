@@ -0,0 +1,142 @@
+use automata::{
+    dfa::{DFAEvaluationError, GenericDFA, Symbol, DFA},
+    dfa_v2::DFAParseError,
+};
+
+// accepts the words `login (view)* logout`
+fn session() -> GenericDFA<String> {
+    let mut dfa = GenericDFA::new();
+    dfa.alphabet = ["login", "view", "logout"].map(String::from).to_vec();
+    dfa.states = 4;
+    dfa.accepting = vec![false, false, true, false];
+    for (from, word, to) in [
+        (0, "login", 1),
+        (0, "view", 3),
+        (0, "logout", 3),
+        (1, "login", 3),
+        (1, "view", 1),
+        (1, "logout", 2),
+    ] {
+        dfa.transition.insert((from, word.to_string()), to);
+    }
+    for word in &dfa.alphabet {
+        dfa.transition.insert((2, word.clone()), 3);
+        dfa.transition.insert((3, word.clone()), 3);
+    }
+    dfa
+}
+
+// accepts byte strings with an even number of zero bytes
+fn even_zeros() -> GenericDFA<u8> {
+    let mut dfa = GenericDFA::new();
+    dfa.alphabet = vec![0, 1, 255];
+    dfa.states = 2;
+    dfa.accepting = vec![true, false];
+    for state in 0..2 {
+        dfa.transition.insert((state, 0), 1 - state);
+        dfa.transition.insert((state, 1), state);
+        dfa.transition.insert((state, 255), state);
+    }
+    dfa
+}
+
+fn words(s: &str) -> Vec<String> {
+    s.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn evaluate_string_symbols() {
+    let dfa = session();
+    assert!(dfa.evaluate_symbols(words("login view view logout")).unwrap());
+    assert!(dfa.evaluate_symbols(words("login logout")).unwrap());
+    assert!(!dfa.evaluate_symbols(words("login view")).unwrap());
+    assert!(!dfa.evaluate_symbols(words("view logout")).unwrap());
+    assert!(matches!(
+        dfa.evaluate_symbols(words("login edit")),
+        Err(DFAEvaluationError::InvalidChar(w)) if w == "edit"
+    ));
+}
+
+#[test]
+fn evaluate_byte_symbols() {
+    let dfa = even_zeros();
+    assert!(dfa.evaluate_symbols([]).unwrap());
+    assert!(dfa.evaluate_symbols([0, 1, 0, 255]).unwrap());
+    assert!(!dfa.evaluate_symbols(b"\x00\x01".iter().copied()).unwrap());
+    assert!(matches!(
+        dfa.evaluate_symbols([1, 2]),
+        Err(DFAEvaluationError::InvalidChar(2))
+    ));
+}
+
+#[test]
+fn v1_round_trip_for_every_symbol_type() {
+    let chars = DFA::open_dfa_file("begins_with_ab.dfa").unwrap();
+    assert_eq!(DFA::from_v1_string(&chars.to_string()).unwrap(), chars);
+    let bytes = even_zeros();
+    assert_eq!(GenericDFA::<u8>::from_v1_string(&bytes.to_string()).unwrap(), bytes);
+    let strings = session();
+    assert_eq!(
+        GenericDFA::<String>::from_v1_string(&strings.to_string()).unwrap(),
+        strings
+    );
+}
+
+#[test]
+fn rejected_alphabet_entries_are_an_error() {
+    // 256 is not a byte, and skipping it would move 1 into its column
+    let text = "2\n1\n0,256,1\n1,0,0\n0,1,1\n";
+    assert!(matches!(
+        GenericDFA::<u8>::from_v1_string(text),
+        Err(DFAParseError::InvalidAlphabetEntry((3, s))) if s == "256"
+    ));
+    let bad_escape = "1\n0\nlogin,bad\\q %alphabet\n0,0\n";
+    let error = GenericDFA::<String>::from_v1_string(bad_escape).unwrap_err();
+    assert_eq!(error.to_string(), "invalid alphabet entry bad\\q on line 3");
+
+    // empty entries, as in an empty alphabet, are still skipped
+    let empty = GenericDFA::<u8>::from_v1_string("1\n0\n %alphabet\n\n").unwrap();
+    assert!(empty.alphabet.is_empty());
+}
+
+#[test]
+fn string_symbols_with_separators_round_trip() {
+    let mut dfa = session();
+    let awkward = [
+        "a,b",
+        "50%",
+        " padded ",
+        "back\\slash",
+        "two\nlines",
+        "tab\there",
+        "ünï",
+    ];
+    for (i, word) in awkward.iter().enumerate() {
+        dfa.alphabet.push(word.to_string());
+        for state in 0..dfa.states {
+            dfa.transition
+                .insert((state, word.to_string()), (state + i as i32) % dfa.states);
+        }
+    }
+    let text = dfa.to_string();
+    assert_eq!(text.lines().count(), 3 + dfa.states as usize);
+    assert_eq!(GenericDFA::<String>::from_v1_string(&text).unwrap(), dfa);
+    for word in awkward {
+        assert_eq!(String::parse(&word.to_string().to_text()).as_deref(), Some(word));
+    }
+}
+
+#[test]
+fn char_symbols_are_unchanged() {
+    assert_eq!('a'.to_text(), "a");
+    assert_eq!('\\'.to_text(), "\\");
+    assert_eq!(char::parse("ab"), Some('a'));
+    assert_eq!(char::parse(""), None);
+    let dfa = DFA::open_dfa_file("begins_with_ab.dfa").unwrap();
+    assert!(dfa
+        .to_string()
+        .starts_with("4 %states\n2 %accepting states\na,b %alphabet\n"));
+    for s in ["", "a", "ab", "abba", "ba", "bb"] {
+        assert_eq!(dfa.evaluate(s).unwrap(), dfa.evaluate_symbols(s.chars()).unwrap());
+    }
+}
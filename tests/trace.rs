@@ -0,0 +1,72 @@
+use automata::dfa::{DFAEvaluationError, DFA};
+
+fn begins_with_ab() -> DFA {
+    DFA::open_dfa_file("begins_with_ab.dfa").unwrap()
+}
+
+#[test]
+fn trace_lists_every_step() {
+    let dfa = begins_with_ab();
+    assert_eq!(dfa.trace("").unwrap(), vec![]);
+    assert_eq!(dfa.trace("abb").unwrap(), vec![(0, 'a', 1), (1, 'b', 2), (2, 'b', 2)]);
+    assert_eq!(dfa.trace("ba").unwrap(), vec![(0, 'b', 3), (3, 'a', 3)]);
+}
+
+#[test]
+fn trace_reports_the_same_errors_as_evaluate() {
+    let dfa = begins_with_ab();
+    assert!(matches!(dfa.trace("az"), Err(DFAEvaluationError::InvalidChar('z'))));
+    assert!(matches!(dfa.evaluate("az"), Err(DFAEvaluationError::InvalidChar('z'))));
+}
+
+#[test]
+fn trace_table_marks_the_dead_state() {
+    let table = begins_with_ab().trace_to_string("bab");
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "step\tfrom\tchar\tto",
+            "1\t0\tb\t3\t<- entered permanently rejecting state",
+            "2\t3\ta\t3",
+            "3\t3\tb\t3",
+            "false",
+        ]
+    );
+}
+
+#[test]
+fn trace_table_keeps_the_steps_before_an_error() {
+    let table = begins_with_ab().trace_to_string("abz");
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "step\tfrom\tchar\tto",
+            "1\t0\ta\t1",
+            "2\t1\tb\t2",
+            "invalid character: z",
+        ]
+    );
+}
+
+#[test]
+fn trace_table_notes_a_dead_start_state() {
+    let dfa = begins_with_ab().with_start(3).unwrap();
+    assert!(dfa
+        .trace_to_string("a")
+        .starts_with("step\tfrom\tchar\tto\nstart state 3 is permanently rejecting\n"));
+}
+
+#[test]
+fn evaluate_command_prints_the_trace() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["evaluate", "--trace", "begins_with_ab.dfa", "ab"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "ab:\nstep\tfrom\tchar\tto\n1\t0\ta\t1\n2\t1\tb\t2\ntrue\n\n"
+    );
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use automata::{
+    dfa::DFA,
+    dfa_gen,
+    transducer::{Transducer, TransducerError},
+};
+use common::{assert_same_language, strings};
+
+// writes every a twice and drops every b
+fn double_a() -> Transducer {
+    let mut t = Transducer::new();
+    t.add_state(true);
+    t.add_transition(0, Some('a'), "aa", 0);
+    t.add_transition(0, Some('b'), "", 0);
+    t
+}
+
+// replaces every a with b and every b with a
+fn swap() -> Transducer {
+    let mut t = Transducer::new();
+    t.add_state(true);
+    t.add_transition(0, Some('a'), "b", 0);
+    t.add_transition(0, Some('b'), "a", 0);
+    t
+}
+
+// reads ab or ac, guessing which one on the a and only writing it once the guess is confirmed
+fn delayed_guess() -> Transducer {
+    let mut t = Transducer::new();
+    for accepting in [false, false, false, true] {
+        t.add_state(accepting);
+    }
+    t.add_transition(0, Some('a'), "x", 1);
+    t.add_transition(0, Some('a'), "x", 2);
+    t.add_transition(1, Some('b'), "y", 3);
+    t.add_transition(2, Some('c'), "z", 3);
+    t
+}
+
+fn strs(strings: &[&str]) -> Vec<String> {
+    strings.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn apply_returns_every_output_sorted() {
+    assert_eq!(Transducer::identity(&['a', 'b']).apply("abba"), strs(&["abba"]));
+    assert_eq!(double_a().apply("abab"), strs(&["aaaa"]));
+    assert_eq!(double_a().apply(""), strs(&[""]));
+    assert!(double_a().apply("ac").is_empty());
+    assert!(Transducer::new().apply("").is_empty());
+
+    let mut either = Transducer::new();
+    either.add_state(false);
+    either.add_state(true);
+    either.add_transition(0, Some('a'), "y", 1);
+    either.add_transition(0, Some('a'), "x", 1);
+    assert_eq!(either.apply("a"), strs(&["x", "y"]));
+    assert!(!either.is_deterministic());
+    assert!(swap().is_deterministic());
+}
+
+#[test]
+fn output_producing_epsilon_cycles_stop_at_the_limit() {
+    let mut t = Transducer::new();
+    t.add_state(true);
+    t.add_transition(0, None, "a", 0);
+    let outputs = t.apply_with_limit("", 10);
+    assert!(!outputs.is_empty() && outputs.len() <= 10);
+    assert!(outputs.iter().all(|s| s.chars().all(|c| c == 'a')));
+    assert_eq!(outputs[0], "");
+}
+
+#[test]
+fn compose_feeds_one_output_into_the_next() {
+    let composed = double_a().compose(&swap());
+    assert_eq!(composed.apply("aba"), strs(&["bbbb"]));
+    let twice = swap().compose(&swap());
+    for s in strings(&['a', 'b'], 4) {
+        assert_eq!(twice.apply(&s), vec![s.clone()]);
+        assert_eq!(
+            swap().compose(&double_a()).apply(&s),
+            double_a().apply(&swap().apply(&s)[0])
+        );
+    }
+    assert!(double_a().compose(&Transducer::new()).apply("a").is_empty());
+}
+
+#[test]
+fn invert_swaps_inputs_and_outputs() {
+    for s in strings(&['a', 'b'], 4) {
+        assert_eq!(swap().invert().apply(&s), swap().apply(&s));
+    }
+    let inverse = double_a().invert();
+    assert_eq!(inverse.input_alphabet, vec!['a']);
+    assert_eq!(inverse.output_alphabet, vec!['a', 'b']);
+    // the dropped b's can be put back anywhere, so the inverse has infinitely many outputs
+    let outputs = inverse.apply_with_limit("aa", 50);
+    for s in ["a", "ab", "ba", "bab"] {
+        assert!(outputs.contains(&String::from(s)), "{s:?}");
+    }
+    assert!(outputs.iter().all(|s| s.chars().filter(|&c| c == 'a').count() == 1));
+    assert!(inverse.apply_with_limit("aaa", 50).is_empty());
+}
+
+#[test]
+fn restrict_domain_and_range() {
+    let dfa = DFA::open_dfa_file("begins_with_ab.dfa").unwrap();
+    let identity = Transducer::identity(&['a', 'b']);
+    let restricted = identity.restrict_domain(&dfa);
+    assert_eq!(restricted.apply("abb"), strs(&["abb"]));
+    assert!(restricted.apply("ba").is_empty());
+    assert_same_language(&restricted.domain(), &dfa, 5);
+    assert_same_language(&identity.range_restricted_to(&dfa), &dfa, 5);
+
+    let begins_with_ba = dfa_gen::begins_with(&['b', 'a'], "ba").unwrap();
+    assert_same_language(&swap().range_restricted_to(&dfa), &begins_with_ba, 5);
+    assert_same_language(&swap().domain(), &dfa_gen::all_strings(&['a', 'b']), 5);
+
+    let range = double_a().range();
+    for s in strings(&['a'], 6) {
+        assert_eq!(range.evaluate(&s).unwrap(), s.len() % 2 == 0, "{s:?}");
+    }
+    assert!(!Transducer::new().domain().evaluate("").unwrap());
+}
+
+#[test]
+fn determinize_delays_output_until_it_is_known() {
+    let t = delayed_guess();
+    let deterministic = t.determinize().unwrap();
+    assert!(deterministic.is_deterministic());
+    for s in strings(&['a', 'b', 'c'], 3) {
+        assert_eq!(deterministic.apply(&s), t.apply(&s), "{s:?}");
+    }
+    assert_eq!(deterministic.apply("ac"), strs(&["xz"]));
+    let deterministic = swap().determinize().unwrap();
+    assert_eq!(deterministic.states, 1);
+}
+
+#[test]
+fn determinize_rejects_relations_that_are_not_functions() {
+    let mut t = Transducer::new();
+    t.add_state(false);
+    t.add_state(true);
+    t.add_state(true);
+    t.add_transition(0, Some('a'), "x", 1);
+    t.add_transition(0, Some('a'), "y", 2);
+    assert!(matches!(t.determinize(), Err(TransducerError::NotSequential)));
+
+    // b is written as x before an odd number of a's and as y before an even number, a function
+    // that is not sequential because the output has to be chosen before the a's are read
+    let mut t = Transducer::new();
+    for accepting in [false, false, true, true, false] {
+        t.add_state(accepting);
+    }
+    t.add_transition(0, Some('b'), "x", 1);
+    t.add_transition(1, Some('a'), "", 2);
+    t.add_transition(2, Some('a'), "", 1);
+    t.add_transition(0, Some('b'), "y", 3);
+    t.add_transition(3, Some('a'), "", 4);
+    t.add_transition(4, Some('a'), "", 3);
+    assert_eq!(t.apply("baaa"), strs(&["x"]));
+    assert!(matches!(t.determinize(), Err(TransducerError::NotSequential)));
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use std::process::Command;
+
+use automata::turing::{Direction, TMEvaluationError, TMResult, TuringMachine, STEP_LIMIT};
+use common::strings;
+
+fn is_anbn(s: &str) -> bool {
+    let n = s.len() / 2;
+    s == "a".repeat(n) + &"b".repeat(n)
+}
+
+// moves right forever on a, halts on b without accepting or rejecting
+fn runner() -> TuringMachine {
+    let mut tm = TuringMachine::new();
+    tm.alphabet = vec!['a', 'b'];
+    tm.tape_alphabet = vec!['_', 'a', 'b'];
+    tm.states = 1;
+    tm.accepting = vec![false];
+    tm.rejecting = vec![false];
+    tm.transition.insert((0, 'a'), (0, 'a', Direction::Right));
+    tm.transition.insert((0, '_'), (0, '_', Direction::Right));
+    tm
+}
+
+#[test]
+fn anbn_machine() {
+    let tm = TuringMachine::open_tm_file("anbn").unwrap();
+    assert_eq!(tm.blank, '_');
+    for s in strings(&['a', 'b'], 8) {
+        let expected = if is_anbn(&s) {
+            TMResult::Accept
+        } else {
+            TMResult::Reject
+        };
+        assert_eq!(tm.run(&s, STEP_LIMIT).unwrap(), expected, "{s:?}");
+    }
+    assert!(matches!(
+        tm.run("abc", STEP_LIMIT),
+        Err(TMEvaluationError::InvalidChar('c'))
+    ));
+    assert_eq!(tm.evaluate_to_string("aabb"), "true");
+    assert_eq!(tm.evaluate_to_string("abb"), "false");
+    assert_eq!(tm.evaluate_to_string("c"), "invalid character: c");
+}
+
+#[test]
+fn halting_and_timeouts() {
+    let tm = runner();
+    assert_eq!(tm.run("aab", 100).unwrap(), TMResult::Halt);
+    assert_eq!(tm.run("aab", 1).unwrap(), TMResult::Timeout);
+    assert_eq!(tm.run("aa", 100).unwrap(), TMResult::Timeout);
+    assert_eq!(
+        tm.evaluate_to_string("b"),
+        "false (halted without reaching an accepting or rejecting state)"
+    );
+    assert_eq!(
+        tm.evaluate_to_string("a"),
+        format!("no result after {STEP_LIMIT} steps")
+    );
+    assert_eq!(TuringMachine::new().run("", 10).unwrap(), TMResult::Halt);
+}
+
+#[test]
+fn moving_left_of_the_input_grows_the_tape() {
+    let mut tm = runner();
+    tm.transition.insert((0, 'a'), (0, 'b', Direction::Left));
+    tm.transition.remove(&(0, '_'));
+    let (result, snapshots) = tm.trace("a", 10).unwrap();
+    assert_eq!(result, TMResult::Halt);
+    let last = snapshots.last().unwrap();
+    assert_eq!((last.head, last.tape.clone()), (0, vec!['_', 'b']));
+    assert_eq!(last.to_string(), "[_]b");
+}
+
+#[test]
+fn targets_outside_the_machine_are_an_error() {
+    let mut tm = runner();
+    tm.transition.insert((0, 'b'), (3, 'b', Direction::Stay));
+    assert!(matches!(tm.run("ab", 10), Err(TMEvaluationError::InvalidState(3))));
+    tm.transition.insert((0, 'b'), (-1, 'b', Direction::Stay));
+    assert!(matches!(tm.run("b", 10), Err(TMEvaluationError::InvalidState(-1))));
+    assert_eq!(tm.evaluate_to_string("b"), "invalid state: -1");
+    assert_eq!(tm.trace_to_string("b"), "invalid state: -1");
+}
+
+#[test]
+fn trace_records_every_configuration() {
+    let tm = TuringMachine::open_tm_file("anbn.tm").unwrap();
+    let (result, snapshots) = tm.trace("ab", STEP_LIMIT).unwrap();
+    assert_eq!(result, TMResult::Accept);
+    let states: Vec<i32> = snapshots.iter().map(|s| s.state).collect();
+    assert_eq!(states, vec![0, 1, 2, 0, 3, 4]);
+    let tapes: Vec<String> = snapshots.iter().map(|s| s.to_string()).collect();
+    assert_eq!(tapes, vec!["[a]b", "X[b]", "[X]Y", "X[Y]", "XY[_]", "XY[_]"]);
+    assert_eq!(tm.trace_to_string(""), "step\tstate\ttape\n0\t0\t[_]\n1\t4\t[_]\ntrue");
+}
+
+#[test]
+fn to_string_round_trip() {
+    let tm = TuringMachine::open_tm_file("anbn.tm").unwrap();
+    let read = TuringMachine::from_string(tm.to_string());
+    assert_eq!(read.states, tm.states);
+    assert_eq!(read.accepting, tm.accepting);
+    assert_eq!(read.rejecting, tm.rejecting);
+    assert_eq!(read.alphabet, tm.alphabet);
+    assert_eq!(read.tape_alphabet, tm.tape_alphabet);
+    assert_eq!(read.blank, tm.blank);
+    assert_eq!(read.transition, tm.transition);
+}
+
+#[test]
+fn evaluate_command() {
+    let output = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .args(["evaluate", "anbn.tm", "aabb", "abb", "c"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "aabb: true\nabb: false\nc: invalid character: c\n"
+    );
+}
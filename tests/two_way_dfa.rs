@@ -0,0 +1,142 @@
+mod common;
+
+use std::{env, fs, process::Command};
+
+use automata::{
+    dfa::{DFAEvaluationError, DFA},
+    dfa_gen,
+    two_way_dfa::{HeadMove, TapeSymbol, TwoWayDFA, TwoWayResult},
+};
+use common::{assert_same_language, strings};
+
+fn third_from_last_is_a(s: &str) -> bool {
+    s.len() >= 3 && s.as_bytes()[s.len() - 3] == b'a'
+}
+
+// reads the input left to right, then walks back to ⊢ and across again, accepting
+// if the first pass saw an even number of a's
+fn there_and_back() -> TwoWayDFA {
+    let mut two_way = TwoWayDFA::new();
+    two_way.alphabet = vec!['a', 'b'];
+    two_way.states = 4;
+    two_way.accepting = vec![false, false, false, true];
+    let mut add = |from, symbol, to, direction| {
+        two_way.transition.insert((from, symbol), (to, direction));
+    };
+    add(0, TapeSymbol::LeftEnd, 0, HeadMove::Right);
+    add(0, TapeSymbol::Symbol('a'), 1, HeadMove::Right);
+    add(0, TapeSymbol::Symbol('b'), 0, HeadMove::Right);
+    add(1, TapeSymbol::Symbol('a'), 0, HeadMove::Right);
+    add(1, TapeSymbol::Symbol('b'), 1, HeadMove::Right);
+    add(0, TapeSymbol::RightEnd, 2, HeadMove::Left);
+    add(2, TapeSymbol::Symbol('a'), 2, HeadMove::Left);
+    add(2, TapeSymbol::Symbol('b'), 2, HeadMove::Left);
+    add(2, TapeSymbol::LeftEnd, 3, HeadMove::Right);
+    for c in ['a', 'b'] {
+        add(3, TapeSymbol::Symbol(c), 3, HeadMove::Right);
+    }
+    add(3, TapeSymbol::RightEnd, 3, HeadMove::Right);
+    two_way
+}
+
+#[test]
+fn targets_outside_the_machine_are_an_error() {
+    for (target, expected) in [("-1", -1), ("2", 2)] {
+        let two_way = TwoWayDFA::from_string(format!("2\n1\na\n,1R,\n1R,,{target}R\n"));
+        assert!(matches!(
+            two_way.simulate(""),
+            Err(DFAEvaluationError::InvalidState(i)) if i == expected
+        ));
+        assert_eq!(two_way.evaluate_to_string(""), format!("invalid state: {expected}"));
+        // the conversion treats the broken transition as a rejecting run
+        assert!(!two_way.to_dfa().evaluate("a").unwrap());
+    }
+}
+
+#[test]
+fn third_from_last_file() {
+    let two_way = TwoWayDFA::open_2dfa_file("third_from_last_is_a").unwrap();
+    for s in strings(&['a', 'b'], 6) {
+        assert_eq!(two_way.evaluate(&s).unwrap(), third_from_last_is_a(&s), "{s:?}");
+    }
+    assert!(matches!(
+        two_way.simulate("abc"),
+        Err(DFAEvaluationError::InvalidChar('c'))
+    ));
+    assert_eq!(two_way.evaluate_to_string("aab"), "true");
+    assert_eq!(two_way.evaluate_to_string("c"), "invalid character: c");
+}
+
+#[test]
+fn runs_that_repeat_a_configuration_loop() {
+    let mut two_way = there_and_back();
+    assert_eq!(two_way.simulate("abab").unwrap(), TwoWayResult::Accept);
+    assert_eq!(two_way.simulate("ab").unwrap(), TwoWayResult::Reject);
+    // bouncing between two cells never ends
+    two_way
+        .transition
+        .insert((3, TapeSymbol::Symbol('b')), (2, HeadMove::Left));
+    two_way
+        .transition
+        .insert((2, TapeSymbol::LeftEnd), (3, HeadMove::Right));
+    assert_eq!(two_way.simulate("b").unwrap(), TwoWayResult::Loop);
+    assert_eq!(two_way.evaluate_to_string("b"), "false (loops forever)");
+    assert!(!two_way.evaluate("b").unwrap());
+    // moving left off ⊢ rejects
+    two_way.transition.insert((0, TapeSymbol::LeftEnd), (0, HeadMove::Left));
+    assert_eq!(two_way.simulate("").unwrap(), TwoWayResult::Reject);
+}
+
+#[test]
+fn to_dfa_accepts_the_same_strings() {
+    let two_way = TwoWayDFA::open_2dfa_file("third_from_last_is_a.2dfa").unwrap();
+    let dfa = two_way.to_dfa();
+    // the smallest dfa remembers the last three characters
+    assert_eq!(dfa.states, 8);
+    for s in strings(&['a', 'b'], 7) {
+        assert_eq!(dfa.evaluate(&s).unwrap(), third_from_last_is_a(&s), "{s:?}");
+    }
+    let even_a = dfa_gen::modulo_n(&['a', 'b'], &['a'], 0, 2).unwrap();
+    assert_same_language(&there_and_back().to_dfa(), &even_a, 6);
+    let mut looping = there_and_back();
+    looping
+        .transition
+        .insert((2, TapeSymbol::Symbol('b')), (0, HeadMove::Right));
+    for s in strings(&['a', 'b'], 6) {
+        let expected = looping.simulate(&s).unwrap() == TwoWayResult::Accept;
+        assert_eq!(looping.to_dfa().evaluate(&s).unwrap(), expected, "{s:?}");
+    }
+    assert!(!TwoWayDFA::new().to_dfa().evaluate("").unwrap());
+}
+
+#[test]
+fn to_string_round_trip() {
+    for two_way in [
+        there_and_back(),
+        TwoWayDFA::open_2dfa_file("third_from_last_is_a.2dfa").unwrap(),
+    ] {
+        let read = TwoWayDFA::from_string(two_way.to_string());
+        assert_eq!(read.states, two_way.states);
+        assert_eq!(read.accepting, two_way.accepting);
+        assert_eq!(read.alphabet, two_way.alphabet);
+        assert_eq!(read.transition, two_way.transition);
+    }
+}
+
+#[test]
+fn conversion_command() {
+    let dir = env::temp_dir().join(format!("automata_2dfa_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("third_from_last_is_a.dfa");
+    let result = Command::new(env!("CARGO_BIN_EXE_dfa"))
+        .arg("2dfa-to-dfa")
+        .arg(&output)
+        .arg("third_from_last_is_a.2dfa")
+        .output()
+        .unwrap();
+    assert!(result.status.success());
+    let dfa = DFA::open_dfa_file(output.to_str().unwrap()).unwrap();
+    let two_way = TwoWayDFA::open_2dfa_file("third_from_last_is_a.2dfa").unwrap();
+    assert_same_language(&dfa, &two_way.to_dfa(), 6);
+    fs::remove_dir_all(dir).unwrap();
+}